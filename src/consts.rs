@@ -0,0 +1,56 @@
+//!Byte counts of each prefix, and `const fn` shorthands for sizes in
+//!them, e.g. `const MAX_UPLOAD: IEC = mib(512);`.
+
+use unit::{IEC_PREFIX, SI_PREFIX};
+use {IEC, SI};
+
+macro_rules! prefix_consts {
+    ($kind: ident, $table: ident, $($index: expr => $konst: ident, $helper: ident, $symbol: expr;)*) => {
+        $(
+        #[doc = concat!("Bytes in one `", $symbol, "`.")]
+        pub const $konst: u64 = $table[$index] as u64;
+
+        #[doc = concat!("`x` ", $symbol, " as a size, usable in `const` items.")]
+        #[inline(always)]
+        pub const fn $helper(x: u64) -> $kind {
+            //cannot overflow, every `u64` multiple of a `u64` fits
+            $kind::from_u128(x as u128 * $konst as u128)
+        }
+        )*
+    };
+}
+
+prefix_consts!(IEC, IEC_PREFIX,
+    1 => KIB, kib, "KiB";
+    2 => MIB, mib, "MiB";
+    3 => GIB, gib, "GiB";
+    4 => TIB, tib, "TiB";
+    5 => PIB, pib, "PiB";
+    6 => EIB, eib, "EiB";
+);
+
+prefix_consts!(SI, SI_PREFIX,
+    1 => KB, kb, "KB";
+    2 => MB, mb, "MB";
+    3 => GB, gb, "GB";
+    4 => TB, tb, "TB";
+    5 => PB, pb, "PB";
+    6 => EB, eb, "EB";
+);
+
+#[test]
+fn test_const() {
+    use IecUnit;
+    const MAX_UPLOAD: IEC = mib(512);
+    const LIMIT: IEC = IEC::new(1536);
+    const BUFFER: [u8; KIB as usize] = [0; KIB as usize];
+    assert_eq!(format!("{}", MAX_UPLOAD), "512.00MiB");
+    assert_eq!(LIMIT.unit(), IecUnit::KiB);
+    assert_eq!(BUFFER.len(), 1024);
+    assert_eq!(EIB, 1 << 60);
+    assert_eq!(format!("{}", eb(u64::MAX)), "18446744.07QB");
+    assert_eq!(format!("{}", gb(4)), "4.00GB");
+    assert_eq!(kib(4), IEC::new(4096));
+    const DELTA: SI = SI::new_signed(-1500);
+    assert_eq!(format!("{}", DELTA), "-1.50KB");
+}
@@ -0,0 +1,234 @@
+//!Human readable durations, such as `1h 02m 03s` or `350ms`.
+
+use core::convert::TryFrom;
+use core::fmt;
+use core::str::FromStr;
+use core::time::Duration;
+
+use format::write_padded;
+use ParseError;
+
+///Components a duration is broken into, largest first: nanoseconds in
+///each, compact symbol, digits to pad to when not leading, long name.
+const PARTS: [(u128, &str, usize, &str); 7] = [
+    (86_400_000_000_000, "d", 1, "day"),
+    (3_600_000_000_000, "h", 2, "hour"),
+    (60_000_000_000, "m", 2, "minute"),
+    (1_000_000_000, "s", 2, "second"),
+    (1_000_000, "ms", 3, "millisecond"),
+    (1_000, "µs", 3, "microsecond"),
+    (1, "ns", 3, "nanosecond"),
+];
+
+///Index of seconds in `PARTS`, used for a zero duration.
+const SECONDS: usize = 3;
+
+///How the components of a `HumanDuration` are written.
+#[derive(Clone,Copy,Debug,Default,PartialEq,Eq,Hash)]
+pub enum DurationStyle {
+    ///`1h 02m 03s`
+    #[default]
+    Short,
+    ///`1 hour 2 minutes 3 seconds`
+    Long,
+}
+
+///A `Duration` that displays in human readable form.
+///
+///Starting from its largest non-zero component, at most `precision`
+///components are written (three by default, or the precision in the
+///format string) and the rest are truncated. Trailing zero components
+///are left out, so `90s` is `1m 30s` and `1h` is just `1h`. The
+///alternate flag, `{:#}`, selects `DurationStyle::Long`.
+#[derive(Clone,Copy,Debug,PartialEq,Eq,Hash)]
+pub struct HumanDuration {
+    duration: Duration,
+    precision: usize,
+    style: DurationStyle,
+}
+
+impl HumanDuration {
+
+    ///Wraps `duration` with the default options.
+    pub fn new(duration: Duration) -> HumanDuration {
+        HumanDuration {
+            duration,
+            precision: 3,
+            style: DurationStyle::Short,
+        }
+    }
+
+    ///Most components to write. Defaults to `3`.
+    pub fn precision(mut self, precision: usize) -> HumanDuration {
+        self.precision = precision;
+        self
+    }
+
+    ///`Short` writes `1h 02m 03s`, `Long` writes
+    ///`1 hour 2 minutes 3 seconds`. Defaults to `Short`.
+    pub fn style(mut self, style: DurationStyle) -> HumanDuration {
+        self.style = style;
+        self
+    }
+
+    ///The wrapped duration.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    fn write_to(&self, out: &mut dyn fmt::Write, precision: usize, style: DurationStyle) -> fmt::Result {
+        let mut rem = self.duration.as_nanos();
+        let first = match PARTS.iter().position(|part| rem >= part.0) {
+            Some(first) => first,
+            None => return write_part(out, 0, SECONDS, true, style),
+        };
+        let mut values = [0u128; 7];
+        for (value, part) in values.iter_mut().zip(PARTS.iter()).skip(first) {
+            *value = rem / part.0;
+            rem %= part.0;
+        }
+        //drop the components that would only be zeros
+        let last = PARTS.len().min(first + precision.max(1));
+        let end = (first..last).rev().find(|&index| values[index] != 0).map_or(first + 1, |index| index + 1);
+        for (index, value) in values.iter().enumerate().take(end).skip(first) {
+            if index > first {
+                out.write_str(" ")?;
+            }
+            write_part(out, *value, index, index == first, style)?;
+        }
+        Ok(())
+    }
+}
+
+///Writes one component, such as `02m` or `2 minutes`.
+fn write_part(out: &mut dyn fmt::Write, value: u128, index: usize, leading: bool, style: DurationStyle) -> fmt::Result {
+    let (_, symbol, pad, name) = PARTS[index];
+    match style {
+        DurationStyle::Long if value == 1 => write!(out, "{} {}", value, name),
+        DurationStyle::Long => write!(out, "{} {}s", value, name),
+        DurationStyle::Short if leading => write!(out, "{}{}", value, symbol),
+        DurationStyle::Short => write!(out, "{:02$}{}", value, symbol, pad),
+    }
+}
+
+impl From<Duration> for HumanDuration {
+    fn from(duration: Duration) -> HumanDuration {
+        HumanDuration::new(duration)
+    }
+}
+
+impl fmt::Display for HumanDuration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let precision = f.precision().unwrap_or(self.precision);
+        let style = if f.alternate() { DurationStyle::Long } else { self.style };
+        write_padded(f, |out| self.write_to(out, precision, style))
+    }
+}
+#[test]
+fn test_display() {
+    let show = |secs: u64, nanos: u32| format!("{}", HumanDuration::new(Duration::new(secs, nanos)));
+    assert_eq!(show(3723, 0), "1h 02m 03s");
+    assert_eq!(show(0, 350_000_000), "350ms");
+    assert_eq!(show(90, 0), "1m 30s");
+    assert_eq!(show(3600, 0), "1h");
+    assert_eq!(show(3603, 0), "1h 00m 03s");
+    assert_eq!(show(1, 500_000_000), "1s 500ms");
+    assert_eq!(show(0, 1_500), "1µs 500ns");
+    assert_eq!(show(0, 0), "0s");
+    assert_eq!(show(2 * 86_400 + 5, 0), "2d");
+    assert_eq!(show(86_400 + 3723, 7), "1d 01h 02m");
+}
+#[test]
+fn test_display_options() {
+    let x = HumanDuration::new(Duration::new(3723, 400_000_000));
+    assert_eq!(format!("{:.2}", x), "1h 02m");
+    assert_eq!(format!("{:.0}", x), "1h");
+    assert_eq!(format!("{}", x.precision(5)), "1h 02m 03s 400ms");
+    assert_eq!(format!("{:#}", x), "1 hour 2 minutes 3 seconds");
+    assert_eq!(format!("{}", HumanDuration::new(Duration::from_secs(1)).style(DurationStyle::Long)), "1 second");
+    assert_eq!(format!("{:#}", HumanDuration::new(Duration::from_secs(0))), "0 seconds");
+    assert_eq!(format!("{:>12}", x), "  1h 02m 03s");
+}
+
+///Parses durations such as `1h30m`, `1h 02m 03s`, `350ms`, `1.5s` or
+///`2 hours 5 minutes`. Every component needs a unit.
+impl FromStr for HumanDuration {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<HumanDuration, ParseError> {
+        let mut rest = s.trim();
+        if rest.is_empty() {
+            return Err(ParseError::Empty);
+        }
+        let mut total: u128 = 0;
+        while !rest.is_empty() {
+            let split = rest.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(rest.len());
+            let (number, tail) = rest.split_at(split);
+            let tail = tail.trim_start();
+            let split = tail.find(|c: char| c.is_ascii_digit() || c.is_whitespace()).unwrap_or(tail.len());
+            let (unit, tail) = tail.split_at(split);
+            let nanos = unit_nanos(unit).ok_or(ParseError::UnknownUnit)?;
+            total = total.checked_add(parse_number(number, nanos)?).ok_or(ParseError::Overflow)?;
+            rest = tail.trim_start();
+        }
+        let secs = u64::try_from(total / 1_000_000_000).map_err(|_| ParseError::Overflow)?;
+        Ok(HumanDuration::new(Duration::new(secs, (total % 1_000_000_000) as u32)))
+    }
+}
+
+///Nanoseconds in the unit named `unit`.
+fn unit_nanos(unit: &str) -> Option<u128> {
+    let index = match unit {
+        "d" | "day" | "days" => 0,
+        "h" | "hr" | "hrs" | "hour" | "hours" => 1,
+        "m" | "min" | "mins" | "minute" | "minutes" => 2,
+        "s" | "sec" | "secs" | "second" | "seconds" => 3,
+        "ms" | "millisecond" | "milliseconds" => 4,
+        "µs" | "us" | "microsecond" | "microseconds" => 5,
+        "ns" | "nanosecond" | "nanoseconds" => 6,
+        _ => return None,
+    };
+    Some(PARTS[index].0)
+}
+
+///
+///`number` times `nanos`, with any fraction of a nanosecond dropped.
+///
+fn parse_number(number: &str, nanos: u128) -> Result<u128, ParseError> {
+    let (whole, frac) = match number.find('.') {
+        Some(dot) => (&number[..dot], &number[dot+1..]),
+        None => (number, ""),
+    };
+    if (whole.is_empty() && frac.is_empty()) || frac.contains('.') {
+        return Err(ParseError::InvalidNumber);
+    }
+    let mut total: u128 = 0;
+    for digit in whole.bytes() {
+        total = total.checked_mul(10)
+            .and_then(|total| total.checked_add((digit - b'0') as u128))
+            .ok_or(ParseError::Overflow)?;
+    }
+    total = total.checked_mul(nanos).ok_or(ParseError::Overflow)?;
+    let mut part: u128 = 0;
+    for digit in frac.bytes().rev() {
+        part = (part + (digit - b'0') as u128 * nanos) / 10;
+    }
+    Ok(total + part)
+}
+#[test]
+fn test_parse() {
+    let parse = |s: &str| s.parse::<HumanDuration>().map(|x| x.duration());
+    assert_eq!(parse("1h30m"), Ok(Duration::from_secs(5400)));
+    assert_eq!(parse("1h 02m 03s"), Ok(Duration::from_secs(3723)));
+    assert_eq!(parse("350ms"), Ok(Duration::from_millis(350)));
+    assert_eq!(parse("1.5s"), Ok(Duration::from_millis(1500)));
+    assert_eq!(parse("2 hours 5 minutes"), Ok(Duration::from_secs(7500)));
+    assert_eq!(parse("1µs 500ns"), Ok(Duration::from_nanos(1500)));
+    assert_eq!(parse(""), Err(ParseError::Empty));
+    assert_eq!(parse("15"), Err(ParseError::UnknownUnit));
+    assert_eq!(parse("5 fortnights"), Err(ParseError::UnknownUnit));
+    assert_eq!(parse("h"), Err(ParseError::InvalidNumber));
+    assert_eq!(parse("99999999999999999999999d"), Err(ParseError::Overflow));
+    let x: HumanDuration = "1d 01h 02m".parse().unwrap();
+    assert_eq!(format!("{}", x), "1d 01h 02m");
+}
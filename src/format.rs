@@ -0,0 +1,706 @@
+//!Configurable display of sizes.
+
+use core::fmt;
+use core::fmt::Write;
+use core::str;
+
+use unit::{self, PREFIX_LETTERS};
+use {Size, TimeBase, Unit};
+#[cfg(test)]
+use {IecUnit, SiUnit, IEC, SI};
+
+///How digits past the displayed precision are handled.
+///
+///Modes apply to the magnitude, so `Ceil` rounds negative values
+///away from zero.
+#[derive(Clone,Copy,Debug,Default,PartialEq,Eq,Hash)]
+pub enum Rounding {
+    ///Round to nearest, ties to even. This matches `f64` formatting.
+    #[default]
+    HalfEven,
+    ///Discard the remaining digits.
+    Truncate,
+    ///Round up if any remaining digit is non-zero.
+    Ceil,
+}
+
+///What goes between the number and the unit symbol.
+#[derive(Clone,Copy,Debug,Default,PartialEq,Eq,Hash)]
+pub enum Separator {
+    ///`1.00KiB`
+    #[default]
+    None,
+    ///`1.00 KiB`
+    Space,
+    ///`1.00\u{a0}KiB`, which keeps the value and unit on one line.
+    NonBreakingSpace,
+}
+
+impl Separator {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Separator::None => "",
+            Separator::Space => " ",
+            Separator::NonBreakingSpace => "\u{a0}",
+        }
+    }
+}
+
+///How the unit is written.
+#[derive(Clone,Copy,Debug,Default,PartialEq,Eq,Hash)]
+pub enum Style {
+    ///Symbols, such as `1.50KiB`.
+    #[default]
+    Short,
+    ///Long names with plurals, such as `1.5 kibibytes` or `1 byte`.
+    ///Trailing zeros are trimmed unless asked otherwise, and a value is
+    ///singular only when it is written as exactly `1`.
+    Long,
+    ///The output of GNU coreutils `ls -h` and `du -h` for `IEC`, or
+    ///`--si` for `SI`, such as `1.5K`, `23M` or `4.0G`.
+    ///
+    ///Values are always rounded up, with one fractional digit below `10`
+    ///and none otherwise, and counts below the base have no suffix. The
+    ///precision, rounding, separator and unit options are ignored, as
+    ///is everything but the decimal point of the `Locale`.
+    Coreutils,
+}
+
+///Which side of the number the unit is written on.
+#[derive(Clone,Copy,Debug,Default,PartialEq,Eq,Hash)]
+pub enum Placement {
+    ///`1.50 KiB`
+    #[default]
+    After,
+    ///`KiB 1.50`
+    Before,
+}
+
+///Number conventions that vary between locales.
+///
+///Nothing is looked up from the environment; callers fill in the
+///settings they want, e.g. `Locale::new().decimal(',').grouping(' ')`
+///for `1 023,00 B`.
+#[derive(Clone,Copy,Debug,PartialEq,Eq,Hash)]
+pub struct Locale {
+    decimal: char,
+    grouping: Option<char>,
+    placement: Placement,
+}
+
+impl Locale {
+
+    ///A `.` decimal point, no digit grouping and the unit after the
+    ///number.
+    pub const fn new() -> Locale {
+        Locale {
+            decimal: '.',
+            grouping: None,
+            placement: Placement::After,
+        }
+    }
+
+    ///Character written between the whole and fractional digits.
+    pub const fn decimal(mut self, decimal: char) -> Locale {
+        self.decimal = decimal;
+        self
+    }
+
+    ///Character written between groups of three whole digits, such as
+    ///`,` for `1,023` or `\u{a0}` for `1\u{a0}023`.
+    pub const fn grouping(mut self, separator: char) -> Locale {
+        self.grouping = Some(separator);
+        self
+    }
+
+    ///Which side of the number the unit goes on.
+    pub const fn placement(mut self, placement: Placement) -> Locale {
+        self.placement = placement;
+        self
+    }
+}
+
+impl Default for Locale {
+    fn default() -> Locale {
+        Locale::new()
+    }
+}
+
+///Options for displaying a `Size`, kept apart from any value so they
+///can be built once and applied to many.
+///
+///Used with `Size::format_with` and `HumanBytes::human_bytes_with`.
+///Every builder is also available on `Formatted`.
+#[derive(Clone,Copy,Debug,PartialEq,Eq,Hash)]
+pub struct FormatOptions<U> {
+    precision: usize,
+    rounding: Rounding,
+    ///`None` trims for `Style::Long` only.
+    trim_zeros: Option<bool>,
+    separator: Separator,
+    style: Style,
+    unit: Option<U>,
+    min_unit: Option<U>,
+    max_unit: Option<U>,
+    locale: Locale,
+}
+
+impl<U: Unit> FormatOptions<U> {
+
+    ///The defaults, which write `1.50KiB`.
+    pub const fn new() -> FormatOptions<U> {
+        FormatOptions {
+            precision: 2,
+            rounding: Rounding::HalfEven,
+            trim_zeros: None,
+            separator: Separator::None,
+            style: Style::Short,
+            unit: None,
+            min_unit: None,
+            max_unit: None,
+            locale: Locale::new(),
+        }
+    }
+}
+
+impl<U: Unit> Default for FormatOptions<U> {
+    fn default() -> FormatOptions<U> {
+        FormatOptions::new()
+    }
+}
+
+///
+///Defines each option builder on `FormatOptions`, and on `Formatted`
+///forwarding to its options.
+///
+macro_rules! format_options {
+    ($($(#[$doc: meta])* fn $name: ident($arg: ident: $kind: ty) => $field: ident = $value: expr;)*) => {
+        impl<U: Unit> FormatOptions<U> {
+            $(
+            $(#[$doc])*
+            pub fn $name(mut self, $arg: $kind) -> FormatOptions<U> {
+                self.$field = $value;
+                self
+            }
+            )*
+        }
+
+        impl<U: Unit> Formatted<U> {
+            $(
+            $(#[$doc])*
+            pub fn $name(mut self, $arg: $kind) -> Formatted<U> {
+                self.options = self.options.$name($arg);
+                self
+            }
+            )*
+        }
+    };
+}
+
+format_options! {
+    ///Number of fractional digits to write. Defaults to `2`.
+    fn precision(precision: usize) => precision = precision;
+
+    ///How to round the last displayed digit. Defaults to `HalfEven`.
+    fn rounding(rounding: Rounding) => rounding = rounding;
+
+    ///Drop trailing zeros after rounding, so `1.50KiB` becomes `1.5KiB`
+    ///and `1.00KiB` becomes `1KiB`. Defaults to trimming for
+    ///`Style::Long` only, which reads `1.5 kibibytes` and `1 byte`.
+    fn trim_zeros(trim: bool) => trim_zeros = Some(trim);
+
+    ///What to put between the number and the unit. Defaults to `None`,
+    ///which is written as a space for `Style::Long`.
+    fn separator(separator: Separator) => separator = separator;
+
+    ///Whether to write unit symbols or long names. Defaults to `Short`.
+    fn style(style: Style) => style = style;
+
+    ///Always display in `unit`, e.g. to keep a whole column in `MiB`.
+    ///Overrides `min_unit` and `max_unit`.
+    fn unit(unit: U) => unit = Some(unit);
+
+    ///Never display in a unit smaller than `unit`.
+    fn min_unit(unit: U) => min_unit = Some(unit);
+
+    ///Never display in a unit larger than `unit`.
+    fn max_unit(unit: U) => max_unit = Some(unit);
+
+    ///Decimal point, digit grouping and unit placement. Defaults to
+    ///`Locale::new()`, which writes `1023.50B`.
+    fn locale(locale: Locale) => locale = locale;
+}
+
+///A `Size` along with the options used to display it.
+///
+///Built with `Size::format` or `Size::format_with`. A precision in the
+///format string, such as `{:.4}`, takes priority over the one
+///configured here. Width, fill and alignment are honored as well,
+///aligning right unless asked otherwise so that columns of sizes line
+///up. The alternate flag, `{:#}`, selects `Style::Long`.
+#[derive(Clone,Copy)]
+pub struct Formatted<U> {
+    size: Size<U>,
+    options: FormatOptions<U>,
+    per: Option<TimeBase>,
+}
+
+impl<U: Unit> Formatted<U> {
+
+    pub(crate) fn new(size: Size<U>, options: FormatOptions<U>) -> Formatted<U> {
+        Formatted { size, options, per: None }
+    }
+
+    ///Writes the value as a rate over `per`, as `Rate` does.
+    pub(crate) fn with_per(mut self, per: TimeBase) -> Formatted<U> {
+        self.per = Some(per);
+        self
+    }
+
+    ///The options the size is displayed with.
+    pub fn options(&self) -> FormatOptions<U> {
+        self.options
+    }
+
+    ///The unit the value is written in once the options are applied.
+    fn display_unit(&self) -> U {
+        if let Some(unit) = self.options.unit {
+            return unit;
+        }
+        let mut unit = self.size.unit;
+        if let Some(min) = self.options.min_unit {
+            if unit.multiplier() < min.multiplier() {
+                unit = min;
+            }
+        }
+        if let Some(max) = self.options.max_unit {
+            if unit.multiplier() > max.multiplier() {
+                unit = max;
+            }
+        }
+        unit
+    }
+
+    fn write_to(&self, out: &mut dyn fmt::Write, precision: usize, style: Style) -> fmt::Result {
+        if style == Style::Coreutils {
+            return self.write_coreutils(out);
+        }
+        let size = &self.size;
+        let unit = self.display_unit();
+        let trim = self.options.trim_zeros.unwrap_or(style == Style::Long);
+        let value = Fixed::new(size.bytes, unit.multiplier(), precision, self.options.rounding, trim);
+        let separator = match (style, self.options.separator) {
+            (Style::Long, Separator::None) => Separator::Space,
+            (_, separator) => separator,
+        };
+        match self.options.locale.placement {
+            Placement::After => {
+                value.write_localized(out, size.negative, &self.options.locale)?;
+                out.write_str(separator.as_str())?;
+                self.write_unit(out, unit, style, value.is_one())
+            }
+            Placement::Before => {
+                self.write_unit(out, unit, style, value.is_one())?;
+                out.write_str(separator.as_str())?;
+                value.write_localized(out, size.negative, &self.options.locale)
+            }
+        }
+    }
+
+    ///Writes the unit, and the time base of a rate, in `style`.
+    fn write_unit(&self, out: &mut dyn fmt::Write, unit: U, style: Style, one: bool) -> fmt::Result {
+        match style {
+            Style::Long => {
+                out.write_str(unit.name())?;
+                if !one {
+                    out.write_str("s")?;
+                }
+                if let Some(per) = self.per {
+                    out.write_str(" per ")?;
+                    out.write_str(per.name())?;
+                }
+            }
+            _ => {
+                out.write_str(unit.symbol())?;
+                if let Some(per) = self.per {
+                    out.write_str(per.symbol())?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    ///
+    ///Follows `human_readable` in coreutils with `human_ceiling`, which
+    ///rounds the magnitude up to one fractional digit below `10` and to
+    ///a whole number otherwise, moving to the next prefix at the base.
+    ///
+    fn write_coreutils(&self, out: &mut dyn fmt::Write) -> fmt::Result {
+        let size = &self.size;
+        if size.negative {
+            out.write_str("-")?;
+        }
+        let mut exponent = unit::position(U::BASE, size.bytes) as u32;
+        if exponent == 0 {
+            write!(out, "{}", size.bytes)?;
+        } else {
+            let mult = U::BASE.pow(exponent);
+            let whole = size.bytes / mult;
+            let ceil = |x: u128| x / mult + if x % mult == 0 { 0 } else { 1 };
+            if whole < 10 {
+                //fits, as `bytes` is below `10 * mult`
+                let tenths = ceil(size.bytes * 10);
+                if tenths < 100 {
+                    write!(out, "{}{}{}", tenths / 10, self.options.locale.decimal, tenths % 10)?;
+                } else {
+                    out.write_str("10")?;
+                }
+            } else {
+                let whole = ceil(size.bytes);
+                if whole == U::BASE && (exponent as usize) < PREFIX_LETTERS.len() {
+                    exponent += 1;
+                    write!(out, "1{}0", self.options.locale.decimal)?;
+                } else {
+                    write!(out, "{}", whole)?;
+                }
+            }
+            if exponent == 1 && U::BASE == 1000 {
+                out.write_str("k")?;
+            } else {
+                out.write_char(PREFIX_LETTERS[exponent as usize - 1] as char)?;
+            }
+        }
+        if let Some(per) = self.per {
+            out.write_str(per.symbol())?;
+        }
+        Ok(())
+    }
+}
+
+///Counts the characters written through it, used to work out padding.
+struct CharCount(usize);
+
+impl fmt::Write for CharCount {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0 += s.chars().count();
+        Ok(())
+    }
+}
+
+///
+///Runs `write` with the width, fill and alignment of `f` applied,
+///aligning right unless asked otherwise. `write` is called twice when
+///a width is given, once to measure.
+///
+pub(crate) fn write_padded<F>(f: &mut fmt::Formatter, write: F) -> fmt::Result
+    where F: Fn(&mut dyn fmt::Write) -> fmt::Result
+{
+    let width = match f.width() {
+        Some(width) => width,
+        None => return write(f),
+    };
+    let mut count = CharCount(0);
+    write(&mut count)?;
+    let padding = width.saturating_sub(count.0);
+    let (before, after) = match f.align() {
+        Some(fmt::Alignment::Left) => (0, padding),
+        Some(fmt::Alignment::Center) => (padding / 2, padding - padding / 2),
+        Some(fmt::Alignment::Right) | None => (padding, 0),
+    };
+    let fill = f.fill();
+    for _ in 0..before {
+        f.write_char(fill)?;
+    }
+    write(f)?;
+    for _ in 0..after {
+        f.write_char(fill)?;
+    }
+    Ok(())
+}
+
+impl<U: Unit> fmt::Debug for Formatted<U> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Formatted")
+            .field("size", &self.size)
+            .field("options", &self.options)
+            .field("per", &self.per)
+            .finish()
+    }
+}
+
+impl<U: Unit> fmt::Display for Formatted<U> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let precision = f.precision().unwrap_or(self.options.precision);
+        let style = if f.alternate() { Style::Long } else { self.options.style };
+        write_padded(f, |out| self.write_to(out, precision, style))
+    }
+}
+
+///Longest run of fractional digits worked out exactly. Every prefix
+///divides a power of ten with no more digits than this (`1024^10`
+///divides `10^100`), so any further digits requested are zeros.
+pub(crate) const MAX_DIGITS: usize = 100;
+
+///A non-negative value rounded to a fixed number of fractional digits.
+pub(crate) struct Fixed {
+    whole: u128,
+    digits: [u8; MAX_DIGITS],
+    ///Digits of `digits` to write.
+    len: usize,
+    ///Zeros to write after them, for precisions past `MAX_DIGITS`.
+    pad: usize,
+}
+
+impl Fixed {
+
+    ///
+    ///Rounds `bytes / mult` to `precision` fractional digits.
+    ///
+    pub(crate) fn new(bytes: u128, mult: u128, precision: usize, rounding: Rounding, trim: bool) -> Fixed {
+        let mut whole = bytes / mult;
+        let mut rem = bytes % mult;
+        let mut digits = [b'0'; MAX_DIGITS];
+        let exact = if precision < MAX_DIGITS { precision } else { MAX_DIGITS };
+        for digit in digits[..exact].iter_mut() {
+            rem *= 10;
+            *digit = b'0' + (rem / mult) as u8;
+            rem %= mult;
+        }
+        let last_odd = match exact {
+            0 => whole % 2 == 1,
+            n => digits[n-1] % 2 == 1,
+        };
+        let round_up = match rounding {
+            Rounding::HalfEven => rem * 2 > mult || (rem * 2 == mult && last_odd),
+            Rounding::Truncate => false,
+            Rounding::Ceil => rem > 0,
+        };
+        if round_up {
+            let mut carry = true;
+            for digit in digits[..exact].iter_mut().rev() {
+                if *digit == b'9' {
+                    *digit = b'0';
+                } else {
+                    *digit += 1;
+                    carry = false;
+                    break;
+                }
+            }
+            if carry {
+                whole += 1;
+            }
+        }
+        let (len, pad) = if trim {
+            let len = digits[..exact].iter().rposition(|&d| d != b'0').map_or(0, |i| i + 1);
+            (len, 0)
+        } else {
+            (exact, precision - exact)
+        };
+        Fixed { whole, digits, len, pad }
+    }
+
+    ///Whole part after rounding.
+    pub(crate) fn whole(&self) -> u128 {
+        self.whole
+    }
+
+    ///Whether the value is written as exactly `1`, which takes the
+    ///singular in long names.
+    fn is_one(&self) -> bool {
+        self.whole == 1 && self.len + self.pad == 0
+    }
+
+    pub(crate) fn write(&self, f: &mut dyn fmt::Write, negative: bool) -> fmt::Result {
+        self.write_localized(f, negative, &Locale::new())
+    }
+
+    pub(crate) fn write_localized(&self, f: &mut dyn fmt::Write, negative: bool, locale: &Locale) -> fmt::Result {
+        if negative {
+            f.write_str("-")?;
+        }
+        match locale.grouping {
+            Some(separator) if self.whole >= 1000 => write_grouped(f, self.whole, separator)?,
+            _ => write!(f, "{}", self.whole)?,
+        }
+        if self.len + self.pad > 0 {
+            f.write_char(locale.decimal)?;
+            //digits are ASCII by construction
+            f.write_str(str::from_utf8(&self.digits[..self.len]).map_err(|_| fmt::Error)?)?;
+            for _ in 0..self.pad {
+                f.write_str("0")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+///Writes `whole` with `separator` between groups of three digits.
+fn write_grouped(f: &mut dyn fmt::Write, whole: u128, separator: char) -> fmt::Result {
+    //`u128::MAX` has 39 digits
+    let mut digits = [0u8; 39];
+    let mut len = 0;
+    let mut rest = whole;
+    loop {
+        digits[len] = b'0' + (rest % 10) as u8;
+        len += 1;
+        rest /= 10;
+        if rest == 0 {
+            break;
+        }
+    }
+    for index in (0..len).rev() {
+        f.write_char(digits[index] as char)?;
+        if index > 0 && index % 3 == 0 {
+            f.write_char(separator)?;
+        }
+    }
+    Ok(())
+}
+
+///
+///Writes every digit of `bytes / mult`, keeping at least one fractional
+///digit the way `f64` debug output does.
+///
+pub(crate) fn write_exact(f: &mut dyn fmt::Write, negative: bool, bytes: u128, mult: u128) -> fmt::Result {
+    Fixed::new(bytes, mult, MAX_DIGITS, Rounding::Truncate, true).write(f, negative)?;
+    if bytes % mult == 0 {
+        f.write_str(".0")?;
+    }
+    Ok(())
+}
+
+#[test]
+fn test_precision() {
+    let x = IEC::new(1535);
+    assert_eq!(format!("{}", x), "1.50KiB");
+    assert_eq!(format!("{:.0}", x), "1KiB");
+    assert_eq!(format!("{:.4}", x), "1.4990KiB");
+    assert_eq!(format!("{:.10}", x), "1.4990234375KiB");
+    assert_eq!(format!("{:.12}", x), "1.499023437500KiB");
+    assert_eq!(format!("{}", x.format().precision(3)), "1.499KiB");
+    assert_eq!(format!("{:.1}", x.format().precision(3)), "1.5KiB");
+}
+#[test]
+fn test_rounding() {
+    let x = SI::new(1_225);
+    assert_eq!(format!("{}", x.format().rounding(Rounding::HalfEven)), "1.22KB");
+    assert_eq!(format!("{}", x.format().rounding(Rounding::Truncate)), "1.22KB");
+    assert_eq!(format!("{}", x.format().rounding(Rounding::Ceil)), "1.23KB");
+    let x = SI::new(1_999);
+    assert_eq!(format!("{}", x.format().rounding(Rounding::Truncate)), "1.99KB");
+    assert_eq!(format!("{}", x.format().rounding(Rounding::Ceil)), "2.00KB");
+    assert_eq!(format!("{}", SI::new_signed(-1_001).format().rounding(Rounding::Ceil)), "-1.01KB");
+}
+#[test]
+fn test_trim_zeros() {
+    assert_eq!(format!("{}", IEC::new(1536).format().trim_zeros(true)), "1.5KiB");
+    assert_eq!(format!("{}", IEC::new(1024).format().trim_zeros(true)), "1KiB");
+    assert_eq!(format!("{:.1}", SI::new(1_999).format().trim_zeros(true)), "2KB");
+    assert_eq!(format!("{}", IEC::new(1024).format().trim_zeros(false)), "1.00KiB");
+}
+#[test]
+fn test_padding() {
+    let x = IEC::new(1024);
+    assert_eq!(format!("{:10}", x), "   1.00KiB");
+    assert_eq!(format!("{:>10}", x), "   1.00KiB");
+    assert_eq!(format!("{:<10}|", x), "1.00KiB   |");
+    assert_eq!(format!("{:*^11}", x), "**1.00KiB**");
+    assert_eq!(format!("{:>10.1}", x), "    1.0KiB");
+    assert_eq!(format!("{:3}", x), "1.00KiB");
+}
+#[test]
+fn test_separator() {
+    let x = IEC::new(1024).format();
+    assert_eq!(format!("{}", x.separator(Separator::None)), "1.00KiB");
+    assert_eq!(format!("{}", x.separator(Separator::Space)), "1.00 KiB");
+    assert_eq!(format!("{}", x.separator(Separator::NonBreakingSpace)), "1.00\u{a0}KiB");
+    assert_eq!(format!("{:>9}", x.separator(Separator::NonBreakingSpace)), " 1.00\u{a0}KiB");
+}
+#[test]
+fn test_unit_options() {
+    let column = [IEC::new(3 << 30), IEC::new(200 << 10), IEC::new(5 << 20)];
+    let text: std::vec::Vec<_> = column.iter().map(|x| format!("{}", x.format().unit(IecUnit::MiB))).collect();
+    assert_eq!(text, ["3072.00MiB", "0.20MiB", "5.00MiB"]);
+    let clamp = |x: IEC| format!("{}", x.format().min_unit(IecUnit::KiB).max_unit(IecUnit::TiB));
+    assert_eq!(clamp(IEC::new(12)), "0.01KiB");
+    assert_eq!(clamp(IEC::new(3 << 20)), "3.00MiB");
+    assert_eq!(clamp(IEC::from_u128(2 << 50)), "2048.00TiB");
+    assert_eq!(format!("{}", SI::new(1500).format().unit(SiUnit::B)), "1500.00B");
+}
+#[test]
+fn test_long_names() {
+    assert_eq!(format!("{}", IEC::new(1536).format().style(Style::Long).trim_zeros(true)), "1.5 kibibytes");
+    assert_eq!(format!("{:#}", IEC::new(1536)), "1.5 kibibytes");
+    assert_eq!(format!("{:#}", IEC::new(1535)), "1.5 kibibytes");
+    assert_eq!(format!("{:#}", IEC::new(1536).format().trim_zeros(false)), "1.50 kibibytes");
+    assert_eq!(format!("{:#.0}", IEC::new(1)), "1 byte");
+    assert_eq!(format!("{:#.0}", IEC::new_signed(-1)), "-1 byte");
+    assert_eq!(format!("{:#.0}", IEC::new(0)), "0 bytes");
+    assert_eq!(format!("{:#}", IEC::new(1)), "1 byte");
+    assert_eq!(format!("{:#}", IEC::new(2)), "2 bytes");
+    for text in &["1.5 kibibytes", "1 byte", "3 mebibytes"] {
+        let x: IEC = text.parse().unwrap();
+        assert_eq!(format!("{:#}", x), *text);
+    }
+    assert_eq!(format!("{:#.0}", SI::new(1_000_000)), "1 megabyte");
+    assert_eq!(format!("{:#.0}", SI::new(1_400_000)), "1 megabyte");
+    let x = SI::new(2_000).format().style(Style::Long).separator(Separator::NonBreakingSpace);
+    assert_eq!(format!("{:.0}", x), "2\u{a0}kilobytes");
+}
+#[test]
+fn test_coreutils() {
+    //`truncate -s N f; ls -lh f` and `ls -l --si f` with GNU coreutils 9
+    let golden: [(u64, &str, &str); 22] = [
+        (0, "0", "0"),
+        (999, "999", "999"),
+        (1_000, "1000", "1.0k"),
+        (1_023, "1023", "1.1k"),
+        (1_024, "1.0K", "1.1k"),
+        (1_025, "1.1K", "1.1k"),
+        (1_536, "1.5K", "1.6k"),
+        (9_999, "9.8K", "10k"),
+        (10_137, "9.9K", "11k"),
+        (10_239, "10K", "11k"),
+        (10_241, "11K", "11k"),
+        (102_400, "100K", "103k"),
+        (998_001, "975K", "999k"),
+        (999_999, "977K", "1.0M"),
+        (1_047_552, "1023K", "1.1M"),
+        (1_047_553, "1.0M", "1.1M"),
+        (1_048_576, "1.0M", "1.1M"),
+        (5_000_000, "4.8M", "5.0M"),
+        (10_238_976, "9.8M", "11M"),
+        (123_456_789, "118M", "124M"),
+        (107_374_182_400, "100G", "108G"),
+        (1_099_511_627_776, "1.0T", "1.1T"),
+    ];
+    for &(bytes, iec, si) in golden.iter() {
+        assert_eq!(format!("{}", IEC::new(bytes).format().style(Style::Coreutils)), iec);
+        assert_eq!(format!("{}", SI::new(bytes).format().style(Style::Coreutils)), si);
+    }
+    assert_eq!(format!("{:>6.3}", IEC::new(1_536).format().style(Style::Coreutils)), "  1.5K");
+    assert_eq!(format!("{}", IEC::from_u128(u128::MAX).format().style(Style::Coreutils)), "268435456Q");
+}
+#[test]
+fn test_locale() {
+    let european = Locale::new().decimal(',').grouping(' ');
+    assert_eq!(format!("{}", IEC::new(1536).format().separator(Separator::Space).locale(european)), "1,50 KiB");
+    assert_eq!(format!("{}", IEC::new(1023).format().precision(0).locale(european)), "1 023B");
+    assert_eq!(format!("{}", IEC::new(3 << 30).format().unit(IecUnit::MiB).locale(european)), "3 072,00MiB");
+    assert_eq!(format!("{}", SI::from_u128(u128::MAX).format().unit(SiUnit::B).locale(european)), "340 282 366 920 938 463 463 374 607 431 768 211 455,00B");
+    let before = Locale::new().placement(Placement::Before);
+    assert_eq!(format!("{}", IEC::new_signed(-1536).format().separator(Separator::Space).locale(before)), "KiB -1.50");
+    assert_eq!(format!("{:#.0}", IEC::new(1).format().locale(before)), "byte 1");
+    assert_eq!(format!("{:>8}", IEC::new(1536).format().locale(Locale::new().decimal(','))), " 1,50KiB");
+    assert_eq!(format!("{}", IEC::new(1536).format().style(Style::Coreutils).locale(european)), "1,5K");
+}
+#[test]
+fn test_options() {
+    let opts = FormatOptions::new().precision(1).separator(Separator::Space);
+    assert_eq!(format!("{}", IEC::new(1536).format_with(opts)), "1.5 KiB");
+    assert_eq!(format!("{}", IEC::new(3 << 20).format_with(opts.unit(IecUnit::KiB))), "3072.0 KiB");
+    assert_eq!(IEC::new(1).format().precision(1).separator(Separator::Space).options(), opts);
+    assert_eq!(FormatOptions::<SiUnit>::default(), FormatOptions::new());
+    assert!(format!("{:?}", IEC::new(1536).format().precision(1).options().style(Style::Long)).contains("Long"));
+    assert!(format!("{:?}", IEC::new(1536).format()).starts_with("Formatted { size: IEC::KiB(1.5), options: FormatOptions"));
+}
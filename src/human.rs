@@ -0,0 +1,56 @@
+//!Formatting integers as sizes without naming the size type.
+
+use {FormatOptions, Formatted, IecUnit, SiUnit, Size, Unit};
+#[cfg(test)]
+use {Separator, Style};
+
+///Display adapters for byte counts held in plain integers.
+///
+///`n.human_iec()` is shorthand for `IEC::from(n).format()`, so
+///`format!("{}", len.human_iec())` needs no type annotations.
+pub trait HumanBytes {
+    ///The count as `IEC`, e.g. `1.50KiB`.
+    fn human_iec(self) -> Formatted<IecUnit>;
+
+    ///The count as `SI`, e.g. `1.54KB`.
+    fn human_si(self) -> Formatted<SiUnit>;
+
+    ///The count displayed with `opts`, e.g.
+    ///`n.human_bytes_with(FormatOptions::<SiUnit>::new().precision(1))`.
+    fn human_bytes_with<U: Unit>(self, opts: FormatOptions<U>) -> Formatted<U>;
+}
+
+macro_rules! human_bytes {
+    ($($code: ty),*) => {
+        $(
+        impl HumanBytes for $code {
+            #[inline(always)]
+            fn human_iec(self) -> Formatted<IecUnit> {
+                Size::from(self).format()
+            }
+
+            #[inline(always)]
+            fn human_si(self) -> Formatted<SiUnit> {
+                Size::from(self).format()
+            }
+
+            #[inline(always)]
+            fn human_bytes_with<U: Unit>(self, opts: FormatOptions<U>) -> Formatted<U> {
+                Size::from(self).format_with(opts)
+            }
+        }
+        )*
+    };
+}
+
+human_bytes!(u8, u16, u32, usize, u64, u128, i8, i16, i32, isize, i64, i128);
+#[test]
+fn test_human_bytes() {
+    assert_eq!(format!("{}", 1536u32.human_iec()), "1.50KiB");
+    assert_eq!(format!("{}", 1536u64.human_si()), "1.54KB");
+    assert_eq!(format!("{:.0}", (-2048i64).human_iec()), "-2KiB");
+    assert_eq!(format!("{:#}", 1usize.human_si().precision(0)), "1 byte");
+    let opts = FormatOptions::<SiUnit>::new().precision(1).separator(Separator::Space);
+    assert_eq!(format!("{}", 2_500_000u64.human_bytes_with(opts)), "2.5 MB");
+    assert_eq!(format!("{}", 0u8.human_bytes_with(opts.style(Style::Long))), "0 bytes");
+}
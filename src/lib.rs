@@ -12,141 +12,538 @@
 //!
 //!* IEC uses base 1024
 //!
-//!The enums contains within respect the naming convention sticking to `KiB`,`GiB` for IEC types.
-//!and using `KB`, or `GB` type enum names for SI types.
+//!Values are held as an exact byte count in a `Size`, with `IEC` and `SI`
+//!naming the two prefix families. The unit enums respect the naming convention
+//!sticking to `KiB`,`GiB` for `IecUnit` and using `KB`, or `GB` for `SiUnit`.
+//!
+//!`IECBits` and `SIBits` do the same for bit counts, such as link speeds
+//!in `Mbit`.
+//!
+//!All of them implement `FromStr`, and `parse_bytes` turns strings such as
+//!`1.5 GiB` or `4k` back into a byte count. `bytes!` does the same at
+//!compile time.
+//!
+//!`HumanBytes` adds `human_iec()` and `human_si()` to the integer types,
+//!so `format!("{}", len.human_iec())` needs no annotations.
+//!
+//!`Metric` writes plain counts with SI prefixes and no `B`, such as
+//!`3.4M rows`, and `HumanDuration` writes times such as `1h 02m 03s`.
+//!
+//!The `serde` feature adds `Serialize` and `Deserialize` for `Size`, see
+//!the `serde` module for the encodings on offer.
 //!
 
 #![no_std]
 
-use core::convert::Into;
+#[cfg(test)]
+#[macro_use]
+extern crate std;
+#[cfg(feature = "serde")]
+extern crate serde as serde_crate;
+#[cfg(all(test, feature = "serde"))]
+extern crate postcard;
+#[cfg(all(test, feature = "serde"))]
+extern crate serde_json;
+
+use core::convert::TryFrom;
+use core::num::TryFromIntError;
+use core::cmp::Ordering;
 use core::fmt;
+use core::hash::{Hash, Hasher};
 
-const iec_prefix: [u64; 7] = [
-    1,
-    1024,
-    1024*1024,
-    1024*1024*1024,
-    1024*1024*1024*1024,
-    1024*1024*1024*1024*1024,
-    1024*1024*1024*1024*1024*1024
-];
+mod consts;
+pub use consts::{kib, mib, gib, tib, pib, eib, kb, mb, gb, tb, pb, eb};
+pub use consts::{KIB, MIB, GIB, TIB, PIB, EIB, KB, MB, GB, TB, PB, EB};
+mod duration;
+pub use duration::{DurationStyle, HumanDuration};
+mod format;
+pub use format::{FormatOptions, Formatted, Locale, Placement, Rounding, Separator, Style};
+mod metric;
+pub use metric::Metric;
+mod human;
+pub use human::HumanBytes;
+mod ops;
+mod parse;
+pub use parse::{parse_bytes, ParseError};
+mod rate;
+#[cfg(feature = "serde")]
+pub mod serde;
+pub use rate::{Rate, TimeBase};
+mod unit;
+pub use unit::{IecBitUnit, IecUnit, SiBitUnit, SiUnit, Unit};
 
+///An exact byte count paired with the prefix used to display it.
 ///
-///Find Position within prefix array
+///The byte count is stored as an integer, so no precision is lost
+///regardless of magnitude; the fractional part shown by `Display` is
+///worked out from it. Negative values are stored as a sign and a
+///magnitude.
 ///
-#[inline(always)]
-fn iec_position(x: u64) -> usize {
-    for item in 0..6 {
-        if x >= iec_prefix[item] && x < iec_prefix[item+1] {
-            return item;
+///Equality, ordering and hashing go by the signed byte count alone,
+///so the same amount compares equal whichever unit it is shown in.
+///
+///With the bit unit families the count is of bits rather than bytes;
+///`convert` moves between the two.
+#[derive(Clone,Copy)]
+pub struct Size<U> {
+    bytes: u128,
+    negative: bool,
+    unit: U,
+}
+
+///A size expressed with IEC (base 1024) prefixes.
+pub type IEC = Size<IecUnit>;
+
+///A size expressed with SI (base 1000) prefixes.
+pub type SI = Size<SiUnit>;
+
+///A number of bits expressed with IEC prefixes, such as `Mibit`.
+pub type IECBits = Size<IecBitUnit>;
+
+///A number of bits expressed with SI prefixes, such as `Gbit`.
+pub type SIBits = Size<SiBitUnit>;
+
+impl<U: Unit> Size<U> {
+
+    ///Builds a value from a sign and magnitude, picking the unit from
+    ///the magnitude. Zero is never negative.
+    #[inline(always)]
+    const fn from_parts(negative: bool, bytes: u128) -> Size<U> {
+        Size {
+            bytes,
+            negative: negative && bytes != 0,
+            unit: U::ALL[unit::position(U::BASE, bytes)],
         }
     }
-    7
+
+    ///Selects the largest prefix not exceeding `x`. Every `u64`
+    ///is accepted: `0` is expressed in bytes and anything past the
+    ///largest prefix is expressed in that prefix.
+    #[inline(always)]
+    pub const fn new(x: u64) -> Size<U> {
+        Size::from_parts(false, x as u128)
+    }
+
+    ///Like `new`, for counts that need more than 64 bits, such as
+    ///capacity summed across many machines.
+    #[inline(always)]
+    pub const fn from_u128(x: u128) -> Size<U> {
+        Size::from_parts(false, x)
+    }
+
+    ///Expresses `x` in `unit` rather than picking the prefix from its
+    ///magnitude, e.g. `IEC::in_unit(200 << 10, IecUnit::MiB)` shows
+    ///as `0.20MiB`.
+    #[inline(always)]
+    pub const fn in_unit(x: u64, unit: U) -> Size<U> {
+        Size::new(x).with_unit(unit)
+    }
+
+    ///The same value expressed in `unit`. Arithmetic picks the prefix
+    ///again from the result, so apply this last.
+    #[inline(always)]
+    pub const fn with_unit(mut self, unit: U) -> Size<U> {
+        self.unit = unit;
+        self
+    }
+
+    ///Signed counterpart of `new`. The prefix is chosen from the
+    ///magnitude and negative inputs produce a negative value, so
+    ///`-3145728` displays as `-3.00MiB`.
+    #[inline(always)]
+    pub const fn new_signed(x: i64) -> Size<U> {
+        Size::from_parts(x < 0, x.unsigned_abs() as u128)
+    }
+
+    ///Signed counterpart of `from_u128`.
+    #[inline(always)]
+    pub const fn from_i128(x: i128) -> Size<U> {
+        Size::from_parts(x < 0, x.unsigned_abs())
+    }
+
+    ///Builds a value from a signed integer, rejecting negative input.
+    ///
+    ///Because `From` is implemented for the signed integers, the
+    ///blanket `TryFrom` impl on `Size` itself is infallible; use this
+    ///when a negative size is an error rather than a delta. Unlike the
+    ///other constructors it is not a `const fn`, as `TryFrom` cannot be
+    ///called in one.
+    #[inline(always)]
+    pub fn try_new(x: i64) -> Result<Size<U>, TryFromIntError> {
+        u64::try_from(x).map(Size::new)
+    }
+
+    ///The prefix this value is expressed in.
+    #[inline(always)]
+    pub const fn unit(&self) -> U {
+        self.unit
+    }
+
+    ///Builder for displaying this value with a non-default precision,
+    ///rounding mode, or with trailing zeros trimmed.
+    #[inline(always)]
+    pub fn format(&self) -> Formatted<U> {
+        Formatted::new(*self, FormatOptions::new())
+    }
+
+    ///Displays this value with options built ahead of time, e.g. one
+    ///`FormatOptions` shared by a whole table.
+    #[inline(always)]
+    pub fn format_with(&self, options: FormatOptions<U>) -> Formatted<U> {
+        Formatted::new(*self, options)
+    }
+
+    ///Value in terms of `unit()`, e.g. `1.5` for 1536 bytes as `KiB`.
+    #[inline(always)]
+    pub fn get_val(&self) -> f64 {
+        self.to_unit(self.unit)
+    }
+
+    ///Value in terms of any unit, e.g. how many `MiB` a `GiB` value is,
+    ///or how many `GB` a `GiB` value is. Bytes count as eight bits when
+    ///`unit` is from a bit family, and the other way around.
+    #[inline(always)]
+    pub fn to_unit<V: Unit>(&self, unit: V) -> f64 {
+        let mult = unit.multiplier();
+        let val = (self.bytes / mult) as f64 + (self.bytes % mult) as f64 / mult as f64;
+        let val = val * (U::BITS as f64 / V::BITS as f64);
+        if self.negative { -val } else { val }
+    }
+
+    ///Exact byte count, e.g. `1610612736` for `1.5GiB`.
+    ///
+    ///Panics if the value is negative or needs more than 64 bits; use
+    ///`u64::try_from` to check for that instead.
+    #[inline(always)]
+    pub const fn as_bytes(&self) -> u64 {
+        if self.negative || self.bytes > u64::MAX as u128 {
+            panic!("size does not fit in a u64");
+        }
+        self.bytes as u64
+    }
+
+    ///Exact byte count with the sign dropped, so `-5` bytes gives `5`;
+    ///check `is_negative` alongside it. Use `TryFrom` for a signed or
+    ///checked conversion to a particular integer type.
+    #[inline(always)]
+    pub const fn magnitude(&self) -> u128 {
+        self.bytes
+    }
+
+    ///Whether the value is below zero.
+    #[inline(always)]
+    pub const fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    ///The same amount in another unit family, picking the prefix again.
+    ///Bytes become eight bits each; going from bits to bytes drops any
+    ///partial byte. `None` if the result overflows.
+    #[inline(always)]
+    pub fn checked_convert<V: Unit>(self) -> Option<Size<V>> {
+        let count = if U::BITS >= V::BITS {
+            self.bytes.checked_mul(U::BITS / V::BITS)?
+        } else {
+            self.bytes / (V::BITS / U::BITS)
+        };
+        Some(Size::from_parts(self.negative, count))
+    }
+
+    ///Like `checked_convert`, e.g. `IEC::new(1 << 20).convert::<IecBitUnit>()`
+    ///is `8.00Mibit`. Panics if the result overflows.
+    #[inline(always)]
+    pub fn convert<V: Unit>(self) -> Size<V> {
+        self.checked_convert().expect("attempt to convert with overflow")
+    }
+}
+#[test]
+fn test_bits() {
+    assert_eq!(format!("{}", IECBits::new(1536)), "1.50Kibit");
+    assert_eq!(format!("{}", SIBits::new(12_400_000)), "12.40Mbit");
+    assert_eq!(format!("{:#.0}", SIBits::new(1)), "1 bit");
+    let link: SIBits = SI::new(125_000_000).convert();
+    assert_eq!(format!("{}", link), "1.00Gbit");
+    assert_eq!(format!("{}", IEC::new(1 << 20).convert::<IecBitUnit>()), "8.00Mibit");
+    assert_eq!(format!("{}", IECBits::new(8193).convert::<IecUnit>()), "1.00KiB");
+    assert_eq!(format!("{}", IEC::new(1 << 30).convert::<SiUnit>()), "1.07GB");
+    assert_eq!(format!("{:?}", SIBits::new_signed(-1500)), "SIBits::Kbit(-1.5)");
+    assert!(IEC::from_u128(u128::MAX).checked_convert::<SiBitUnit>().is_none());
+    assert_eq!(IEC::new(8).to_unit(IecBitUnit::Bit), 64.0);
+    assert_eq!(IEC::new(1 << 20).to_unit(SiBitUnit::Mbit), 8.388608);
+    assert_eq!(SIBits::new(8000).to_unit(SiUnit::KB), 1.0);
+    assert_eq!(IECBits::new_signed(-12).to_unit(IecUnit::B), -1.5);
+}
+#[test]
+fn test_in_unit() {
+    const COLUMN: IEC = IEC::in_unit(3 << 30, IecUnit::MiB);
+    let x = COLUMN;
+    assert_eq!(x.unit(), IecUnit::MiB);
+    assert_eq!(x.get_val(), 3072.0);
+    assert_eq!(format!("{}", IEC::in_unit(200 << 10, IecUnit::MiB)), "0.20MiB");
+    assert_eq!(x, IEC::new(3 << 30));
+    assert_eq!(format!("{}", SI::new_signed(-15).with_unit(SiUnit::KB)), "-0.02KB");
+}
+#[test]
+fn test_to_bytes() {
+    assert_eq!(IEC::new(1536).as_bytes(), 1536);
+    assert_eq!(IEC::new(u64::MAX).as_bytes(), u64::MAX);
+    assert_eq!(IEC::new(1536).magnitude(), 1536);
+    assert_eq!(IEC::new_signed(-1536).magnitude(), 1536);
+    assert_eq!(i64::try_from(IEC::new_signed(-1536)), Ok(-1536));
+    assert!(u64::try_from(IEC::new_signed(-1536)).is_err());
+    assert!(IEC::new_signed(-1536).is_negative());
+    assert_eq!(IEC::new(3 << 30).to_unit(IecUnit::MiB), 3072.0);
+    assert_eq!(IEC::new(1 << 30).to_unit(SiUnit::MB), 1073.741824);
+    assert_eq!(IEC::new_signed(-512).to_unit(IecUnit::KiB), -0.5);
+    let x: IEC = "1.5GiB".parse().unwrap();
+    assert_eq!(u64::try_from(x), Ok(1610612736));
+    assert_eq!((x * 2 - x).as_bytes(), 1610612736);
+    assert_eq!(u64::try_from(x * 2 - x), Ok(1610612736));
+    assert!(u64::try_from(IEC::new_signed(-1)).is_err());
+    assert!(u64::try_from(IEC::from_u128(1 << 64)).is_err());
+    assert_eq!(i64::try_from(IEC::new_signed(i64::MIN)), Ok(i64::MIN));
+    assert!(i64::try_from(IEC::new(i64::MAX as u64 + 1)).is_err());
+    assert_eq!(i128::try_from(IEC::from_i128(i128::MIN)), Ok(i128::MIN));
+    assert_eq!(u128::try_from(IEC::from_u128(u128::MAX)), Ok(u128::MAX));
 }
 #[test]
-fn test_iec_position() {
-    assert_eq!(iec_position(5),0);
-    assert_eq!(iec_position(5000),1);
-    assert_eq!(iec_position(1073741824),3);
+#[should_panic]
+fn test_as_bytes_negative() {
+    IEC::new_signed(-1536).as_bytes();
+}
+
+impl<U> PartialEq for Size<U> {
+    fn eq(&self, other: &Size<U>) -> bool {
+        self.negative == other.negative && self.bytes == other.bytes
+    }
 }
 
+impl<U> Eq for Size<U> {}
 
-///Holds a value that represents the fractional portion of a IEC/JEDEC
-///binary prefix.
-///
-///Please note: these are not SI prefixes. They are defined by powers of
-///1024 not 1000 like SI.
-#[derive(Clone,Copy,PartialEq,PartialOrd)]
-pub enum IEC {
-    B(f64),
-    KiB(f64),
-    MiB(f64),
-    GiB(f64),
-    TiB(f64),
-    PiB(f64),
-    EiB(f64),
-}
-
-impl IEC {
-    
-    #[inline(always)]
-    pub fn new(x: u64) -> IEC {
-        let index = iec_position(x);
-        let item = iec_prefix[index] as f64;
-        let thing = x as f64;
-        let item = thing/item;
-        match index {
-            0 => IEC::B(item),
-            1 => IEC::KiB(item),
-            2 => IEC::MiB(item),
-            3 => IEC::GiB(item),
-            4 => IEC::TiB(item),
-            5 => IEC::PiB(item),
-            6 => IEC::EiB(item),
-            _ => unreachable!()
+impl<U> PartialOrd for Size<U> {
+    fn partial_cmp(&self, other: &Size<U>) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<U> Ord for Size<U> {
+    fn cmp(&self, other: &Size<U>) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, false) => self.bytes.cmp(&other.bytes),
+            (true, true) => other.bytes.cmp(&self.bytes),
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
         }
     }
+}
 
-    #[inline(always)]
-    pub fn get_val(&self) -> f64 {
-        match self {
-            &IEC::B(x) => x,
-            &IEC::KiB(x) => x,
-            &IEC::MiB(x) => x,
-            &IEC::GiB(x) => x,
-            &IEC::TiB(x) => x,
-            &IEC::PiB(x) => x,
-            &IEC::EiB(x) => x,
+impl<U> Hash for Size<U> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.negative.hash(state);
+        self.bytes.hash(state);
+    }
+}
+#[test]
+fn test_ordering() {
+    use std::collections::BTreeMap;
+
+    assert_eq!("1KiB".parse::<IEC>(), "1024B".parse::<IEC>());
+    assert!(IEC::new(1023) < IEC::new(1024));
+    assert!(IEC::new(1 << 20) > IEC::new(1000));
+    assert!(IEC::new_signed(-2048) < IEC::new_signed(-1));
+    assert!(IEC::new_signed(-1) < IEC::new(0));
+    assert_eq!(IEC::new_signed(0), IEC::new(0));
+
+    let mut sizes = [IEC::new(5 << 30), IEC::new_signed(-10), IEC::new(700), IEC::new(3 << 10)];
+    sizes.sort();
+    assert_eq!(sizes, [IEC::new_signed(-10), IEC::new(700), IEC::new(3 << 10), IEC::new(5 << 30)]);
+
+    let mut listing = BTreeMap::new();
+    listing.insert(IEC::new(2048), "b");
+    listing.insert(IEC::new(1), "a");
+    assert_eq!(listing.get(&"2KiB".parse().unwrap()), Some(&"b"));
+    assert_eq!(listing.values().cloned().collect::<std::vec::Vec<_>>(), ["a", "b"]);
+}
+
+#[test]
+fn test_iec_new_total() {
+    assert_eq!(IEC::new(0).unit(), IecUnit::B);
+    assert_eq!(IEC::new(0).get_val(), 0.0);
+    assert_eq!(IEC::new(1023).unit(), IecUnit::B);
+    assert_eq!(IEC::new(1 << 60).unit(), IecUnit::EiB);
+    assert_eq!(IEC::new(u64::MAX).unit(), IecUnit::EiB);
+    assert_eq!(IEC::new(u64::MAX).get_val(), 16.0);
+
+    //every power of two, its neighbours, and a pseudo-random sweep
+    let check = |x: u64| {
+        let size = IEC::new(x);
+        let val = size.get_val();
+        let x = x as u128;
+        //f64 rounding can lift values just under a boundary to 1024.0
+        assert!((0.0..=1024.0).contains(&val), "{} -> {}", x, val);
+        assert_eq!(size.bytes, x);
+        let unit = size.unit();
+        assert!(unit == IecUnit::B || x >= unit.multiplier());
+        assert!(x < unit.multiplier() * 1024);
+    };
+    for shift in 0..64 {
+        let x = 1u64 << shift;
+        check(x - 1);
+        check(x);
+        check(x + 1);
+    }
+    check(u64::MAX);
+    let mut state = 0x2545_f491_4f6c_dd1du64;
+    for _ in 0..100_000 {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        check(state >> (state % 64));
+    }
+}
+
+macro_rules! from_trait {
+    (signed $($code: ty),*) => {
+        $(
+        impl<U: Unit> From<$code> for Size<U> {
+            #[inline(always)]
+            fn from(x: $code) -> Size<U> {
+                Size::from_i128(x as i128)
+            }
+        }
+        )*
+    };
+    ($($code: ty),*) => {
+        $(
+        impl<U: Unit> From<$code> for Size<U> {
+            #[inline(always)]
+            fn from(x: $code) -> Size<U> {
+                Size::from_u128(x as u128)
+            }
         }
+        )*
+    };
+}
+
+from_trait!(u8, u16, u32, usize, u64, u128);
+from_trait!(signed i8, i16, i32, isize, i64, i128);
+
+///The error returned when a size does not fit in the requested
+///integer type, either because it is too large or because it is
+///negative and the type is unsigned.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub struct TryFromSizeError(());
+
+impl fmt::Display for TryFromSizeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f,"size out of range for integer type")
     }
 }
 
-macro_rules! into_trait {
+macro_rules! try_from_trait {
+    (signed $code: ty) => {
+        impl<U: Unit> TryFrom<Size<U>> for $code {
+            type Error = TryFromSizeError;
+
+            fn try_from(x: Size<U>) -> Result<$code, TryFromSizeError> {
+                let magnitude = <$code>::MIN.unsigned_abs() as u128;
+                match x.negative {
+                    true if x.bytes == magnitude => Ok(<$code>::MIN),
+                    true if x.bytes < magnitude => Ok(-(x.bytes as $code)),
+                    false if x.bytes < magnitude => Ok(x.bytes as $code),
+                    _ => Err(TryFromSizeError(())),
+                }
+            }
+        }
+    };
     ($code: ty) => {
-        impl Into<IEC> for $code {
-            fn into(self) -> IEC {
-                IEC::new(self as u64)
+        impl<U: Unit> TryFrom<Size<U>> for $code {
+            type Error = TryFromSizeError;
+
+            fn try_from(x: Size<U>) -> Result<$code, TryFromSizeError> {
+                if x.negative || x.bytes > <$code>::MAX as u128 {
+                    return Err(TryFromSizeError(()));
+                }
+                Ok(x.bytes as $code)
             }
         }
     };
 }
 
-into_trait!(u8);
-into_trait!(u16);
-into_trait!(u32);
-into_trait!(usize);
-into_trait!(u64);
-into_trait!(i8);
-into_trait!(i16);
-into_trait!(i32);
-into_trait!(isize);
-into_trait!(i64);
+try_from_trait!(u64);
+try_from_trait!(u128);
+try_from_trait!(signed i64);
+try_from_trait!(signed i128);
 
-impl fmt::Display for IEC {
+///Writes the value with two fractional digits, or with the precision
+///given by the format string, e.g. `{:.0}` or `{:.4}`. The alternate
+///flag writes long unit names, e.g. `{:#}` gives `1.5 kibibytes`.
+impl<U: Unit> fmt::Display for Size<U> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            &IEC::B(x) => write!(f,"{:.*}B",2,x),
-            &IEC::KiB(x) => write!(f,"{:.*}KiB",2,x),
-            &IEC::MiB(x) => write!(f,"{:.*}MiB",2,x),
-            &IEC::GiB(x) => write!(f,"{:.*}GiB",2,x),
-            &IEC::TiB(x) => write!(f,"{:.*}TiB",2,x),
-            &IEC::PiB(x) => write!(f,"{:.*}PiB",2,x),
-            &IEC::EiB(x) => write!(f,"{:.*}EiB",2,x),
-        }
+        fmt::Display::fmt(&self.format(), f)
     }
 }
-impl fmt::Debug for IEC {
+///Shows the unit and the exact value held, such as
+///`IEC::KiB(1.4990234375)`. The alternate form, `{:#?}`, also gives
+///the byte count.
+impl<U: Unit> fmt::Debug for Size<U> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            &IEC::B(x) => write!(f,"{:.*}B",2,x),
-            &IEC::KiB(x) => write!(f,"{:.*}KiB",2,x),
-            &IEC::MiB(x) => write!(f,"{:.*}MiB",2,x),
-            &IEC::GiB(x) => write!(f,"{:.*}GiB",2,x),
-            &IEC::TiB(x) => write!(f,"{:.*}TiB",2,x),
-            &IEC::PiB(x) => write!(f,"{:.*}PiB",2,x),
-            &IEC::EiB(x) => write!(f,"{:.*}EiB",2,x),
+        let mult = self.unit.multiplier();
+        write!(f, "{}::{:?}(", U::FAMILY, self.unit)?;
+        format::write_exact(f, self.negative, self.bytes, mult)?;
+        f.write_str(")")?;
+        if f.alternate() {
+            let sign = if self.negative { "-" } else { "" };
+            write!(f, " = {}{} bytes", sign, self.bytes)?;
         }
+        Ok(())
     }
 }
-        
-
+#[test]
+fn test_debug() {
+    assert_eq!(format!("{:?}", IEC::new(1535)), "IEC::KiB(1.4990234375)");
+    assert_eq!(format!("{:?}", IEC::new(1024)), "IEC::KiB(1.0)");
+    assert_eq!(format!("{:?}", SI::new_signed(-1500)), "SI::KB(-1.5)");
+    assert_eq!(format!("{:?}", IEC::new(0)), "IEC::B(0.0)");
+    assert_eq!(format!("{:#?}", IEC::new(1535)), "IEC::KiB(1.4990234375) = 1535 bytes");
+    assert_eq!(format!("{:#?}", IEC::new_signed(-3)), "IEC::B(-3.0) = -3 bytes");
+}
+#[test]
+fn test_u128() {
+    assert_eq!(format!("{}", IEC::from_u128(1 << 70)), "1.00ZiB");
+    assert_eq!(format!("{}", IEC::from_u128(3 << 80)), "3.00YiB");
+    assert_eq!(format!("{}", IEC::from_u128(1 << 90)), "1.00RiB");
+    assert_eq!(format!("{}", IEC::from_u128(u128::MAX)), "268435456.00QiB");
+    assert_eq!(format!("{}", SI::from_u128(1_500_000_000_000_000_000_000)), "1.50ZB");
+    assert_eq!(format!("{}", IEC::from_i128(i128::MIN)), "-134217728.00QiB");
+    let x: IEC = (5u128 << 60).into();
+    assert_eq!(x.unit(), IecUnit::EiB);
+}
+#[test]
+fn test_exact_display() {
+    //1.225 is not representable as f64 and would round up to 1.23
+    assert_eq!(format!("{}", SI::new(1_225_000_000_000_000_000)), "1.22EB");
+    assert_eq!(format!("{}", SI::new(1_235_000_000_000_000_000)), "1.24EB");
+    assert_eq!(format!("{}", IEC::new(u64::MAX)), "16.00EiB");
+    assert_eq!(format!("{}", IEC::new(1023 * 1024 + 1023)), "1024.00KiB");
+    assert_eq!(format!("{}", IEC::new(0)), "0.00B");
+    let x = (1u64 << 53) + 1;
+    assert_eq!(IEC::new(x).bytes, x as u128);
+}
+#[test]
+fn test_signed_display() {
+    let x: IEC = (-3i32 * 1024 * 1024).into();
+    assert_eq!(format!("{}", x), "-3.00MiB");
+    let x: SI = (-1500i64).into();
+    assert_eq!(format!("{}", x), "-1.50KB");
+    assert_eq!(format!("{}", IEC::new_signed(i64::MIN)), "-8.00EiB");
+    assert!(IEC::try_new(-1).is_err());
+    assert_eq!(IEC::try_new(2048).unwrap(), IEC::new(2048));
+}
+#[test]
+fn test_si_display() {
+    assert_eq!(format!("{}", SI::new(999)), "999.00B");
+    assert_eq!(format!("{}", SI::new(1500)), "1.50KB");
+    let x: SI = 2_000_000_000u64.into();
+    assert_eq!(format!("{}", x), "2.00GB");
+}
@@ -0,0 +1,329 @@
+//!Counts with SI metric prefixes, such as `3.4M rows` or `12.5ms`.
+
+use core::fmt;
+use core::fmt::Write;
+
+use format::{write_padded, Fixed, Rounding, Separator};
+use unit::{position, SI_PREFIX};
+
+///Metric prefixes from `10^-9` to `10^30`, with `PREFIX[UNITY]` being
+///no prefix at all.
+const PREFIX: [&str; 14] = ["n", "µ", "m", "", "k", "M", "G", "T", "P", "E", "Z", "Y", "R", "Q"];
+
+///Index of the empty prefix in `PREFIX`.
+const UNITY: usize = 3;
+
+///`1000` to the power of each prefix, for scaling `f64` values.
+const SCALE: [f64; 14] = [1e-9, 1e-6, 1e-3, 1e0, 1e3, 1e6, 1e9, 1e12, 1e15, 1e18, 1e21, 1e24, 1e27, 1e30];
+
+#[derive(Clone,Copy,Debug,PartialEq)]
+enum Value {
+    ///Exact magnitude of an integer count, and whether it is negative.
+    Count(bool, u128),
+    Real(f64),
+}
+
+///A quantity displayed with SI metric prefixes and no `B` suffix, such
+///as `1.2k`, `3.4M` or `7G`.
+///
+///Integer counts are kept exactly and use the same base-1000 logic as
+///`SI`. `f64` values may also take the sub-unit prefixes `m`, `µ` and
+///`n`, which suits latencies and other measurements. By default one
+///fractional digit is written and trailing zeros are dropped. A
+///precision in the format string, such as `{:.3}`, takes priority, and
+///width, fill and alignment are honored as for `Size`.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct Metric<'a> {
+    value: Value,
+    precision: usize,
+    trim_zeros: bool,
+    separator: Separator,
+    unit: &'a str,
+    attached: bool,
+}
+
+impl<'a> Metric<'a> {
+
+    fn with_value(value: Value) -> Metric<'a> {
+        Metric {
+            value,
+            precision: 1,
+            trim_zeros: true,
+            separator: Separator::None,
+            unit: "",
+            attached: false,
+        }
+    }
+
+    ///A count, which is never written with a prefix below one.
+    #[inline(always)]
+    pub fn new(x: u64) -> Metric<'a> {
+        Metric::from_u128(x as u128)
+    }
+
+    ///Like `new`, for counts that need more than 64 bits.
+    #[inline(always)]
+    pub fn from_u128(x: u128) -> Metric<'a> {
+        Metric::with_value(Value::Count(false, x))
+    }
+
+    ///A count that may be negative, such as a change in row count.
+    #[inline(always)]
+    pub fn new_signed(x: i64) -> Metric<'a> {
+        Metric::from_i128(x as i128)
+    }
+
+    ///Signed counterpart of `from_u128`.
+    #[inline(always)]
+    pub fn from_i128(x: i128) -> Metric<'a> {
+        Metric::with_value(Value::Count(x < 0, x.unsigned_abs()))
+    }
+
+    ///A measured value, which may be written with the prefixes below
+    ///one such as `m` for `0.0125`.
+    pub fn from_f64(x: f64) -> Metric<'a> {
+        Metric::with_value(Value::Real(x))
+    }
+
+    ///Number of fractional digits to write. Defaults to `1`.
+    pub fn precision(mut self, precision: usize) -> Metric<'a> {
+        self.precision = precision;
+        self
+    }
+
+    ///Drop trailing zeros after rounding. Defaults to `true`, so
+    ///`7000000000` is written `7G` rather than `7.0G`.
+    pub fn trim_zeros(mut self, trim: bool) -> Metric<'a> {
+        self.trim_zeros = trim;
+        self
+    }
+
+    ///What to put between the number and the prefix. Defaults to `None`.
+    pub fn separator(mut self, separator: Separator) -> Metric<'a> {
+        self.separator = separator;
+        self
+    }
+
+    ///Name of what is counted, written after a space, such as `rows`
+    ///in `3.4M rows`.
+    pub fn unit(mut self, unit: &'a str) -> Metric<'a> {
+        self.unit = unit;
+        self.attached = false;
+        self
+    }
+
+    ///Unit symbol written straight after the prefix, such as `s` in
+    ///`12.5ms`.
+    pub fn symbol(mut self, symbol: &'a str) -> Metric<'a> {
+        self.unit = symbol;
+        self.attached = true;
+        self
+    }
+
+    fn write_to(&self, out: &mut dyn fmt::Write, precision: usize) -> fmt::Result {
+        let prefix = match self.value {
+            Value::Count(negative, count) => {
+                let round = |exponent: usize| {
+                    Fixed::new(count, SI_PREFIX[exponent], precision, Rounding::HalfEven, self.trim_zeros)
+                };
+                let mut exponent = position(SI_PREFIX[1], count);
+                let mut value = round(exponent);
+                //`999_999` rounds to `1000.0k`, which is `1M`
+                if value.whole() >= 1000 && exponent + 1 < SI_PREFIX.len() {
+                    exponent += 1;
+                    value = round(exponent);
+                }
+                value.write(out, negative)?;
+                PREFIX[UNITY + exponent]
+            }
+            Value::Real(x) if !x.is_finite() => return write!(out, "{}", x),
+            Value::Real(x) => {
+                let mut index = real_position(if x < 0.0 { -x } else { x });
+                let mut whole = WholeDigits(0, false);
+                write!(whole, "{:.*}", precision, x / SCALE[index])?;
+                if whole.0 > 3 && index + 1 < SCALE.len() {
+                    index += 1;
+                }
+                //`+ 0.0` turns negative zero into zero
+                let scaled = x / SCALE[index] + 0.0;
+                if self.trim_zeros {
+                    write!(TrimZeros::new(out), "{:.*}", precision, scaled)?;
+                } else {
+                    write!(out, "{:.*}", precision, scaled)?;
+                }
+                PREFIX[index]
+            }
+        };
+        if !self.unit.is_empty() && !self.attached {
+            out.write_str(self.separator.as_str())?;
+            out.write_str(prefix)?;
+            out.write_str(" ")?;
+            out.write_str(self.unit)
+        } else if !prefix.is_empty() || !self.unit.is_empty() {
+            out.write_str(self.separator.as_str())?;
+            out.write_str(prefix)?;
+            out.write_str(self.unit)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+///
+///Index into `PREFIX` of the largest prefix not exceeding `x`, which
+///must be non-negative. Zero takes no prefix.
+///
+#[inline(always)]
+fn real_position(x: f64) -> usize {
+    if x == 0.0 {
+        return UNITY;
+    }
+    for item in (1..SCALE.len()).rev() {
+        if x >= SCALE[item] {
+            return item;
+        }
+    }
+    0
+}
+
+///Counts the digits written before any decimal point, to spot values
+///that round up to the next prefix.
+struct WholeDigits(usize, bool);
+
+impl fmt::Write for WholeDigits {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            match c {
+                '.' => self.1 = true,
+                '0'..='9' if !self.1 => self.0 += 1,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+///Passes text through, dropping trailing zeros after a decimal point
+///and the point itself if nothing is left behind it.
+struct TrimZeros<'w> {
+    out: &'w mut dyn fmt::Write,
+    fraction: bool,
+    ///A `.` has been held back.
+    point: bool,
+    ///Zeros held back.
+    zeros: usize,
+}
+
+impl<'w> TrimZeros<'w> {
+    fn new(out: &'w mut dyn fmt::Write) -> TrimZeros<'w> {
+        TrimZeros { out, fraction: false, point: false, zeros: 0 }
+    }
+}
+
+impl<'w> fmt::Write for TrimZeros<'w> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            match c {
+                '.' => {
+                    self.fraction = true;
+                    self.point = true;
+                }
+                '0' if self.fraction => self.zeros += 1,
+                c => {
+                    if self.point {
+                        self.out.write_char('.')?;
+                        self.point = false;
+                    }
+                    for _ in 0..self.zeros {
+                        self.out.write_char('0')?;
+                    }
+                    self.zeros = 0;
+                    self.out.write_char(c)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a> fmt::Display for Metric<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let precision = f.precision().unwrap_or(self.precision);
+        write_padded(f, |out| self.write_to(out, precision))
+    }
+}
+
+macro_rules! metric_from {
+    (signed $($kind: ty),*) => {
+        $(
+        impl<'a> From<$kind> for Metric<'a> {
+            #[inline(always)]
+            fn from(x: $kind) -> Metric<'a> {
+                Metric::from_i128(x as i128)
+            }
+        }
+        )*
+    };
+    ($($kind: ty),*) => {
+        $(
+        impl<'a> From<$kind> for Metric<'a> {
+            #[inline(always)]
+            fn from(x: $kind) -> Metric<'a> {
+                Metric::from_u128(x as u128)
+            }
+        }
+        )*
+    };
+}
+metric_from!(u8, u16, u32, usize, u64, u128);
+metric_from!(signed i8, i16, i32, isize, i64, i128);
+
+impl<'a> From<f32> for Metric<'a> {
+    #[inline(always)]
+    fn from(x: f32) -> Metric<'a> {
+        Metric::from_f64(x as f64)
+    }
+}
+
+impl<'a> From<f64> for Metric<'a> {
+    #[inline(always)]
+    fn from(x: f64) -> Metric<'a> {
+        Metric::from_f64(x)
+    }
+}
+#[test]
+fn test_counts() {
+    assert_eq!(format!("{}", Metric::new(1_234)), "1.2k");
+    assert_eq!(format!("{}", Metric::new(3_400_000).unit("rows")), "3.4M rows");
+    assert_eq!(format!("{}", Metric::new(7_000_000_000)), "7G");
+    assert_eq!(format!("{}", Metric::new(12_000).unit("req")), "12k req");
+    assert_eq!(format!("{}", Metric::new(999)), "999");
+    assert_eq!(format!("{}", Metric::new(5).unit("rows")), "5 rows");
+    assert_eq!(format!("{}", Metric::new_signed(-1_250)), "-1.2k");
+    assert_eq!(format!("{:.2}", Metric::new(1_234)), "1.23k");
+    assert_eq!(format!("{}", Metric::new(1_000).trim_zeros(false)), "1.0k");
+    assert_eq!(format!("{}", Metric::new(1_500).separator(Separator::Space).symbol("Hz")), "1.5 kHz");
+    assert_eq!(format!("{:>6}", Metric::from(2_000_000u32)), "    2M");
+    assert_eq!(format!("{}", Metric::from_u128(u128::MAX)), "340282366.9Q");
+    assert_eq!(format!("{}", Metric::new(999_999)), "1M");
+    assert_eq!(format!("{}", Metric::new(999_950)), "1M");
+    assert_eq!(format!("{}", Metric::new(999_949)), "999.9k");
+    assert_eq!(format!("{:.0}", Metric::new(999_500)), "1M");
+    assert_eq!(format!("{}", Metric::new(999_999).trim_zeros(false)), "1.0M");
+}
+#[test]
+fn test_reals() {
+    assert_eq!(format!("{}", Metric::from_f64(0.0125).symbol("s")), "12.5ms");
+    assert_eq!(format!("{}", Metric::from(0.000_042).symbol("s")), "42µs");
+    assert_eq!(format!("{}", Metric::from(3.5e-9).symbol("s")), "3.5ns");
+    assert_eq!(format!("{}", Metric::from(2e-12).symbol("s")), "0ns");
+    assert_eq!(format!("{}", Metric::from(1.5e6).unit("ops")), "1.5M ops");
+    assert_eq!(format!("{}", Metric::from(-0.25)), "-250m");
+    assert_eq!(format!("{}", Metric::from(0.0)), "0");
+    assert_eq!(format!("{}", Metric::from(-0.0)), "0");
+    assert_eq!(format!("{:.3}", Metric::from(0.0125).trim_zeros(false)), "12.500m");
+    assert_eq!(format!("{}", Metric::from(f64::INFINITY)), "inf");
+    assert_eq!(format!("{}", Metric::from(0.99999)), "1");
+    assert_eq!(format!("{}", Metric::from(-999_960.0)), "-1M");
+    assert_eq!(format!("{}", Metric::from(0.999_94)), "999.9m");
+}
@@ -0,0 +1,173 @@
+//!Arithmetic on sizes.
+//!
+//!Operations work on the exact byte count and pick the prefix again
+//!for the result, so `used + delta` stays a `Size` without passing
+//!through `get_val`.
+
+use core::iter::Sum;
+use core::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+
+use {Size, Unit};
+#[cfg(test)]
+use {IecUnit, IEC, SI};
+
+impl<U: Unit> Size<U> {
+
+    ///Adds two signed magnitudes. `None` if the magnitude overflows.
+    #[inline(always)]
+    fn signed_add(self, rhs_negative: bool, rhs_bytes: u128) -> Option<Size<U>> {
+        if self.negative == rhs_negative {
+            let bytes = self.bytes.checked_add(rhs_bytes)?;
+            Some(Size::from_parts(self.negative, bytes))
+        } else if self.bytes >= rhs_bytes {
+            Some(Size::from_parts(self.negative, self.bytes - rhs_bytes))
+        } else {
+            Some(Size::from_parts(rhs_negative, rhs_bytes - self.bytes))
+        }
+    }
+
+    ///Sum of the two byte counts, or `None` on overflow.
+    #[inline(always)]
+    pub fn checked_add(self, rhs: Size<U>) -> Option<Size<U>> {
+        self.signed_add(rhs.negative, rhs.bytes)
+    }
+
+    ///Difference of the two byte counts, or `None` on overflow.
+    #[inline(always)]
+    pub fn checked_sub(self, rhs: Size<U>) -> Option<Size<U>> {
+        self.signed_add(!rhs.negative, rhs.bytes)
+    }
+
+    ///Byte count scaled by `rhs`, or `None` on overflow.
+    #[inline(always)]
+    pub fn checked_mul(self, rhs: u64) -> Option<Size<U>> {
+        let bytes = self.bytes.checked_mul(rhs as u128)?;
+        Some(Size::from_parts(self.negative, bytes))
+    }
+
+    ///Byte count divided by `rhs`, rounding toward zero, or `None` if
+    ///`rhs` is zero.
+    #[inline(always)]
+    pub fn checked_div(self, rhs: u64) -> Option<Size<U>> {
+        let bytes = self.bytes.checked_div(rhs as u128)?;
+        Some(Size::from_parts(self.negative, bytes))
+    }
+
+    ///Like `checked_add`, clamping the magnitude on overflow.
+    #[inline(always)]
+    pub fn saturating_add(self, rhs: Size<U>) -> Size<U> {
+        self.checked_add(rhs).unwrap_or_else(|| Size::from_parts(self.negative, u128::MAX))
+    }
+
+    ///Like `checked_sub`, clamping the magnitude on overflow.
+    #[inline(always)]
+    pub fn saturating_sub(self, rhs: Size<U>) -> Size<U> {
+        self.checked_sub(rhs).unwrap_or_else(|| Size::from_parts(self.negative, u128::MAX))
+    }
+
+    ///Like `checked_mul`, clamping the magnitude on overflow.
+    #[inline(always)]
+    pub fn saturating_mul(self, rhs: u64) -> Size<U> {
+        self.checked_mul(rhs).unwrap_or_else(|| Size::from_parts(self.negative, u128::MAX))
+    }
+}
+
+impl<U: Unit> Add for Size<U> {
+    type Output = Size<U>;
+
+    ///Panics if the magnitude overflows.
+    fn add(self, rhs: Size<U>) -> Size<U> {
+        self.checked_add(rhs).expect("attempt to add with overflow")
+    }
+}
+
+impl<U: Unit> Sub for Size<U> {
+    type Output = Size<U>;
+
+    ///Panics if the magnitude overflows.
+    fn sub(self, rhs: Size<U>) -> Size<U> {
+        self.checked_sub(rhs).expect("attempt to subtract with overflow")
+    }
+}
+
+impl<U: Unit> Mul<u64> for Size<U> {
+    type Output = Size<U>;
+
+    ///Panics if the magnitude overflows.
+    fn mul(self, rhs: u64) -> Size<U> {
+        self.checked_mul(rhs).expect("attempt to multiply with overflow")
+    }
+}
+
+impl<U: Unit> Div<u64> for Size<U> {
+    type Output = Size<U>;
+
+    ///Panics if `rhs` is zero.
+    fn div(self, rhs: u64) -> Size<U> {
+        self.checked_div(rhs).expect("attempt to divide by zero")
+    }
+}
+
+impl<U: Unit> Neg for Size<U> {
+    type Output = Size<U>;
+
+    fn neg(self) -> Size<U> {
+        Size::from_parts(!self.negative, self.bytes)
+    }
+}
+
+impl<U: Unit> AddAssign for Size<U> {
+    fn add_assign(&mut self, rhs: Size<U>) {
+        *self = *self + rhs;
+    }
+}
+
+impl<U: Unit> SubAssign for Size<U> {
+    fn sub_assign(&mut self, rhs: Size<U>) {
+        *self = *self - rhs;
+    }
+}
+
+impl<U: Unit> Sum for Size<U> {
+    fn sum<I: Iterator<Item = Size<U>>>(iter: I) -> Size<U> {
+        iter.fold(Size::new(0), Add::add)
+    }
+}
+
+impl<'a, U: Unit> Sum<&'a Size<U>> for Size<U> {
+    fn sum<I: Iterator<Item = &'a Size<U>>>(iter: I) -> Size<U> {
+        iter.fold(Size::new(0), |acc, x| acc + *x)
+    }
+}
+#[test]
+fn test_arithmetic() {
+    let used = IEC::new(1023);
+    let total = used + IEC::new(1);
+    assert_eq!(total.unit(), IecUnit::KiB);
+    assert_eq!(format!("{}", total), "1.00KiB");
+    assert_eq!(format!("{}", IEC::new(1024) - IEC::new(3 << 20)), "-3.00MiB");
+    assert_eq!(format!("{}", IEC::new_signed(-5) + IEC::new(2)), "-3.00B");
+    assert_eq!(format!("{}", IEC::new_signed(-5) + IEC::new(5)), "0.00B");
+    assert_eq!(format!("{}", IEC::new(512) * 4), "2.00KiB");
+    assert_eq!(format!("{}", IEC::new(3 << 20) / 3), "1.00MiB");
+    assert_eq!(format!("{}", -SI::new(1500)), "-1.50KB");
+    let mut running = SI::new(0);
+    running += SI::new(600);
+    running -= SI::new(100);
+    assert_eq!(format!("{}", running), "500.00B");
+    let parts = [SI::new(500), SI::new(1500), SI::new(1_000_000)];
+    assert_eq!(format!("{}", parts.iter().sum::<SI>()), "1.00MB");
+    assert_eq!(format!("{}", parts.iter().cloned().sum::<SI>()), "1.00MB");
+}
+#[test]
+fn test_checked_saturating() {
+    let max = IEC::from_u128(u128::MAX);
+    assert!(max.checked_add(IEC::new(1)).is_none());
+    assert!(max.checked_mul(2).is_none());
+    assert!(IEC::new(1).checked_div(0).is_none());
+    assert!((-max).checked_sub(IEC::new(1)).is_none());
+    assert_eq!(max.checked_sub(IEC::new(1)).map(|x| x.get_val()), Some(max.get_val()));
+    assert_eq!(max.saturating_add(IEC::new(1)), max);
+    assert_eq!(max.saturating_mul(3), max);
+    assert_eq!((-max).saturating_sub(IEC::new(1)), -max);
+}
@@ -0,0 +1,425 @@
+//!Parsing human readable sizes back into byte counts.
+
+use core::fmt;
+use core::str::FromStr;
+
+use unit::{IEC_PREFIX, PREFIX_LETTERS, SI_PREFIX};
+use {Size, Unit};
+#[cfg(test)]
+use {IecUnit, SiUnit, IECBits, SIBits, IEC, SI};
+
+///Reasons a size string can fail to parse.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum ParseError {
+    ///The input was empty or only whitespace.
+    Empty,
+    ///The numeric portion was missing or malformed.
+    InvalidNumber,
+    ///The suffix did not name a known unit.
+    UnknownUnit,
+    ///The value does not fit in the target type.
+    Overflow,
+}
+
+impl ParseError {
+
+    ///Description of the error, as written by `Display`.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            ParseError::Empty => "empty size string",
+            ParseError::InvalidNumber => "invalid number in size string",
+            ParseError::UnknownUnit => "unknown unit in size string",
+            ParseError::Overflow => "size is too large",
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+///
+///Length of the UTF-8 character at the start of `s` if it is
+///whitespace, otherwise `0`.
+///
+const fn whitespace_len(s: &[u8]) -> usize {
+    let (code, len) = match *s {
+        [a, ..] if a < 0x80 => (a as u32, 1),
+        [a, b, ..] if a & 0xe0 == 0xc0 => (((a & 0x1f) as u32) << 6 | (b & 0x3f) as u32, 2),
+        [a, b, c, ..] if a & 0xf0 == 0xe0 => {
+            (((a & 0x0f) as u32) << 12 | ((b & 0x3f) as u32) << 6 | (c & 0x3f) as u32, 3)
+        }
+        _ => return 0,
+    };
+    //the characters `char::is_whitespace` accepts, which is not const
+    match code {
+        0x09..=0x0d | 0x20 | 0x85 | 0xa0 | 0x1680 | 0x2000..=0x200a
+        | 0x2028 | 0x2029 | 0x202f | 0x205f | 0x3000 => len,
+        _ => 0,
+    }
+}
+
+///
+///`a == b` ignoring ASCII case, as `<[u8]>::eq_ignore_ascii_case` but
+///usable in `const fn`.
+///
+const fn eq_ignore_case(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut index = 0;
+    while index < a.len() {
+        if !a[index].eq_ignore_ascii_case(&b[index]) {
+            return false;
+        }
+        index += 1;
+    }
+    true
+}
+
+///
+///`s` without leading and trailing whitespace, as `str::trim` but
+///usable in `const fn`.
+///
+const fn trim(s: &[u8]) -> &[u8] {
+    let mut start = 0;
+    while start < s.len() {
+        match whitespace_len(s.split_at(start).1) {
+            0 => break,
+            len => start += len,
+        }
+    }
+    let s = s.split_at(start).1;
+    //walk forward, as UTF-8 is only easy to decode in that direction
+    let mut end = 0;
+    let mut index = 0;
+    while index < s.len() {
+        match whitespace_len(s.split_at(index).1) {
+            0 => {
+                index += 1;
+                //skip continuation bytes
+                while index < s.len() && s[index] & 0xc0 == 0x80 {
+                    index += 1;
+                }
+                end = index;
+            }
+            len => index += len,
+        }
+    }
+    s.split_at(end).0
+}
+
+///Leading part of the long names of the binary prefixes, from `K` up.
+const IEC_NAMES: [&str; 10] = ["kibi", "mebi", "gibi", "tebi", "pebi", "exbi", "zebi", "yobi", "robi", "quebi"];
+
+///Leading part of the long names of the decimal prefixes, from `k` up.
+const SI_NAMES: [&str; 10] = ["kilo", "mega", "giga", "tera", "peta", "exa", "zetta", "yotta", "ronna", "quetta"];
+
+///
+///Like `multiplier`, for long names such as `kibibytes` or `megabit`
+///as written by `Style::Long`. Matching ignores ASCII case.
+///
+const fn long_multiplier(unit: &[u8]) -> Option<(u128, u128)> {
+    let unit = match unit.split_last() {
+        Some((b's' | b'S', unit)) => unit,
+        _ => unit,
+    };
+    let (prefix, bits) = match (unit.len().checked_sub(4), unit.len().checked_sub(3)) {
+        (Some(split), _) if eq_ignore_case(unit.split_at(split).1, b"byte") => (unit.split_at(split).0, 8),
+        (_, Some(split)) if eq_ignore_case(unit.split_at(split).1, b"bit") => (unit.split_at(split).0, 1),
+        _ => return None,
+    };
+    if prefix.is_empty() {
+        return Some((1, bits));
+    }
+    let mut index = 0;
+    while index < IEC_NAMES.len() {
+        if eq_ignore_case(prefix, IEC_NAMES[index].as_bytes()) {
+            return Some((IEC_PREFIX[index + 1], bits));
+        }
+        if eq_ignore_case(prefix, SI_NAMES[index].as_bytes()) {
+            return Some((SI_PREFIX[index + 1], bits));
+        }
+        index += 1;
+    }
+    None
+}
+
+///
+///Maps a unit suffix to its multiplier and the bits in the quantity
+///it counts.
+///
+///A trailing `B` means bytes and a trailing `b` or `bit` means bits, so
+///`MB` is a megabyte and `Mb` a megabit; only this letter is case
+///sensitive. Bare prefix letters (`k`, `M`, `G`, ...) and the `Ki`/`KiB`
+///forms are binary bytes, while `KB` is decimal. For bits, `kb` and
+///`kbit` are decimal and `Kib` and `Kibit` binary. No suffix means
+///bytes. Long names are accepted as well.
+///
+const fn multiplier(unit: &[u8]) -> Option<(u128, u128)> {
+    if let Some(found) = long_multiplier(unit) {
+        return Some(found);
+    }
+    let (prefix, bits, suffixed) = match unit.len().checked_sub(3) {
+        Some(split) if eq_ignore_case(unit.split_at(split).1, b"bit") => (unit.split_at(split).0, 1, true),
+        _ => match unit.split_last() {
+            Some((b'b', prefix)) => (prefix, 1, true),
+            Some((b'B', prefix)) => (prefix, 8, true),
+            _ => (unit, 8, false),
+        },
+    };
+    if prefix.is_empty() {
+        return Some((1, bits));
+    }
+    let (letter, rest) = prefix.split_at(1);
+    let mut index = 0;
+    while !PREFIX_LETTERS[index].eq_ignore_ascii_case(&letter[0]) {
+        index += 1;
+        if index == PREFIX_LETTERS.len() {
+            return None;
+        }
+    }
+    //`K` and `Ki` are binary, `KB` and `kb` decimal
+    let binary = if eq_ignore_case(rest, b"i") {
+        true
+    } else if rest.is_empty() {
+        !suffixed
+    } else {
+        return None;
+    };
+    if binary {
+        Some((IEC_PREFIX[index + 1], bits))
+    } else {
+        Some((SI_PREFIX[index + 1], bits))
+    }
+}
+
+///
+///Splits `s` into sign, mantissa and unit, and returns the sign and the
+///magnitude as a count of `bits`-bit quantities, i.e. `8` for bytes.
+///Fractions of a byte (or bit) are discarded.
+///
+///This is a `const fn` so that `bytes!` can run it at compile time,
+///hence the index loops.
+///
+const fn parse_parts(s: &str, bits: u128) -> Result<(bool, u128), ParseError> {
+    let s = trim(s.as_bytes());
+    if s.is_empty() {
+        return Err(ParseError::Empty);
+    }
+    let (negative, s) = match *s {
+        [b'-', ref rest @ ..] => (true, rest),
+        _ => (false, s),
+    };
+    let mut split = 0;
+    let mut dot = None;
+    while split < s.len() && (s[split].is_ascii_digit() || s[split] == b'.') {
+        if s[split] == b'.' {
+            if dot.is_some() {
+                return Err(ParseError::InvalidNumber);
+            }
+            dot = Some(split);
+        }
+        split += 1;
+    }
+    let (number, unit) = s.split_at(split);
+    let (whole, frac) = match dot {
+        Some(dot) => {
+            let (whole, frac) = number.split_at(dot);
+            (whole, frac.split_at(1).1)
+        }
+        None => (number, &[] as &[u8]),
+    };
+    if whole.is_empty() && frac.is_empty() {
+        return Err(ParseError::InvalidNumber);
+    }
+    let (mult, unit_bits) = match multiplier(trim(unit)) {
+        Some(found) => found,
+        None => return Err(ParseError::UnknownUnit),
+    };
+    //bytes read as bits scale up exactly; bits read as bytes are divided
+    //once the whole amount is known
+    let (mult, divisor) = if unit_bits >= bits {
+        (mult * (unit_bits / bits), 1)
+    } else {
+        (mult, bits / unit_bits)
+    };
+
+    let mut total: u128 = 0;
+    let mut index = 0;
+    while index < whole.len() {
+        let digit = (whole[index] - b'0') as u128;
+        total = match total.checked_mul(10) {
+            Some(total) if total <= u128::MAX - digit => total + digit,
+            _ => return Err(ParseError::Overflow),
+        };
+        index += 1;
+    }
+    total = match total.checked_mul(mult) {
+        Some(total) => total,
+        None => return Err(ParseError::Overflow),
+    };
+    //floor(0.d1d2..dn * mult), evaluated from the last digit so every
+    //intermediate division stays exact
+    let mut part: u128 = 0;
+    let mut index = frac.len();
+    while index > 0 {
+        index -= 1;
+        part = (part + (frac[index] - b'0') as u128 * mult) / 10;
+    }
+    match total.checked_add(part) {
+        Some(total) => Ok((negative, total / divisor)),
+        None => Err(ParseError::Overflow),
+    }
+}
+
+///
+///Parses a human readable size such as `512MiB`, `1.5 GiB` or `4k`
+///into a byte count.
+///
+///Every suffix written by the `Display` impl of `Size` is
+///accepted, as are bare prefix letters which are treated as binary.
+///Bit suffixes such as `Mbit` are converted at eight bits a byte.
+///Being a `const fn`, it can also be used through `bytes!`.
+///
+pub const fn parse_bytes(s: &str) -> Result<u64, ParseError> {
+    match parse_parts(s, 8) {
+        Ok((true, _)) => Err(ParseError::InvalidNumber),
+        Ok((false, bytes)) if bytes > u64::MAX as u128 => Err(ParseError::Overflow),
+        Ok((false, bytes)) => Ok(bytes as u64),
+        Err(error) => Err(error),
+    }
+}
+#[test]
+fn test_parse_bytes() {
+    assert_eq!(parse_bytes("512MiB"), Ok(512 * 1024 * 1024));
+    assert_eq!(parse_bytes(" 1.5 GiB "), Ok(1536 * 1024 * 1024));
+    assert_eq!(parse_bytes("4k"), Ok(4096));
+    assert_eq!(parse_bytes("2.5KB"), Ok(2500));
+    assert_eq!(parse_bytes("0.1KiB"), Ok(102));
+    assert_eq!(parse_bytes("17"), Ok(17));
+    assert_eq!(parse_bytes("16EiB"), Err(ParseError::Overflow));
+    assert_eq!(parse_bytes("18446744073709551615B"), Ok(u64::MAX));
+    assert_eq!(parse_bytes("18446744073709551616"), Err(ParseError::Overflow));
+    assert_eq!(parse_bytes("  "), Err(ParseError::Empty));
+    assert_eq!(parse_bytes("1.2.3MiB"), Err(ParseError::InvalidNumber));
+    assert_eq!(parse_bytes("MiB"), Err(ParseError::InvalidNumber));
+    assert_eq!(parse_bytes("-1MiB"), Err(ParseError::InvalidNumber));
+    assert_eq!(parse_bytes("3 furlongs"), Err(ParseError::UnknownUnit));
+    assert_eq!(parse_bytes("3 kbytes"), Err(ParseError::UnknownUnit));
+    assert_eq!(parse_bytes("12 Mbit"), Ok(1_500_000));
+    assert_eq!(parse_bytes("1Kibit"), Ok(128));
+    assert_eq!(parse_bytes("9bit"), Ok(1));
+    assert_eq!(parse_bytes("100Mb"), Ok(12_500_000));
+    assert_eq!(parse_bytes("100MB"), Ok(100_000_000));
+    assert_eq!(parse_bytes("8 kb"), Ok(1000));
+    assert_eq!(parse_bytes("8 Kib"), Ok(1024));
+    assert_eq!(parse_bytes("2 kB"), Ok(2000));
+    assert_eq!(parse_bytes("16b"), Ok(2));
+    assert_eq!(parse_bytes("1.5 kibibytes"), Ok(1536));
+    assert_eq!(parse_bytes("1 Byte"), Ok(1));
+    assert_eq!(parse_bytes("2 kilobytes"), Ok(2000));
+    assert_eq!(parse_bytes("8 megabits"), Ok(1_000_000));
+    assert_eq!(parse_bytes("3 kilobots"), Err(ParseError::UnknownUnit));
+    for (index, unit) in IecUnit::ALL[1..].iter().enumerate() {
+        assert!(unit.name().starts_with(IEC_NAMES[index]));
+        assert!(SiUnit::ALL[index + 1].name().starts_with(SI_NAMES[index]));
+    }
+    assert_eq!(parse_bytes("1.00\u{a0}KiB\u{2009}"), Ok(1024));
+    assert_eq!(parse_bytes("\u{3000}2 mebibytes\n"), Ok(2 << 20));
+}
+
+///
+///Parses a human readable size at compile time into a `u64` byte
+///count, e.g. `bytes!("64 MiB")` or `bytes!(64 MiB)`.
+///
+///Accepts what `parse_bytes` does. Written without quotes, the number
+///and unit need a space between them. An unknown unit or a size that
+///does not fit in a `u64` is a compile error.
+///
+///```
+///#[macro_use]
+///extern crate humannums;
+///
+///const BUFFER: u64 = bytes!(64 KiB);
+///
+///fn main() {
+///    assert_eq!(BUFFER, 65536);
+///}
+///```
+///
+///```compile_fail
+///#[macro_use]
+///extern crate humannums;
+///
+///fn main() {
+///    let _ = bytes!(5 fortnights);
+///}
+///```
+///
+///```compile_fail
+///#[macro_use]
+///extern crate humannums;
+///
+///fn main() {
+///    let _ = bytes!("16 EiB");
+///}
+///```
+///
+#[macro_export]
+macro_rules! bytes {
+    (@parse $size: expr) => {{
+        const BYTES: u64 = match $crate::parse_bytes($size) {
+            Ok(bytes) => bytes,
+            Err(error) => panic!("{}", error.as_str()),
+        };
+        BYTES
+    }};
+    ($size: literal) => {
+        $crate::bytes!(@parse concat!($size))
+    };
+    ($($size: tt)+) => {
+        $crate::bytes!(@parse stringify!($($size)+))
+    };
+}
+#[test]
+fn test_bytes_macro() {
+    const BUFFER: usize = bytes!("64 KiB") as usize;
+    static LIMIT: u64 = bytes!(1.5 GiB);
+    assert_eq!(BUFFER, 64 << 10);
+    assert_eq!(LIMIT, 1536 << 20);
+    assert_eq!(bytes!(4 k), 4096);
+    assert_eq!(bytes!(2.5 KB), 2500);
+    assert_eq!(bytes!("12Mbit"), 1_500_000);
+    assert_eq!(bytes!(" 17 "), 17);
+    assert_eq!(bytes!(4096), 4096);
+    const FAILED: Result<u64, ParseError> = parse_bytes("5 fortnights");
+    assert_eq!(FAILED, Err(ParseError::UnknownUnit));
+}
+
+impl<U: Unit> FromStr for Size<U> {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Size<U>, ParseError> {
+        let (negative, bytes) = parse_parts(s, U::BITS)?;
+        Ok(Size::from_parts(negative, bytes))
+    }
+}
+#[test]
+fn test_from_str_round_trip() {
+    for &x in &[0u64, 1, 1023, 1536, 3 * 1024 * 1024, 5 << 40] {
+        let text = format!("{}", IEC::new(x));
+        assert_eq!(text.parse::<IEC>(), Ok(IEC::new(x)));
+    }
+    assert_eq!("-3.00MiB".parse::<IEC>(), Ok(IEC::new_signed(-3 * 1024 * 1024)));
+    assert_eq!("1.50KB".parse::<SI>(), Ok(SI::new(1500)));
+    assert_eq!("2 ZiB".parse::<IEC>(), Ok(IEC::from_u128(2 << 70)));
+    assert_eq!("1QB".parse::<SI>(), Ok(SI::from_u128(SiUnit::QB.multiplier())));
+    assert_eq!("1000000000QiB".parse::<IEC>(), Err(ParseError::Overflow));
+    assert_eq!("12.40Mbit".parse::<SIBits>(), Ok(SIBits::new(12_400_000)));
+    assert_eq!("1.5 Kibit".parse::<IECBits>(), Ok(IECBits::new(1536)));
+    assert_eq!("1KiB".parse::<IECBits>(), Ok(IECBits::new(8192)));
+    assert_eq!("1 bit".parse::<IECBits>(), Ok(IECBits::new(1)));
+}
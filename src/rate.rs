@@ -0,0 +1,178 @@
+//!Throughput, such as `12.40MiB/s`.
+
+use core::fmt;
+use core::str::FromStr;
+use core::time::Duration;
+
+use {Formatted, ParseError, Size, Unit};
+#[cfg(test)]
+use {IecUnit, SiBitUnit, SiUnit, Separator, Style, IEC, SIBits};
+
+///The span of time a `Rate` is expressed over.
+#[derive(Clone,Copy,Debug,Default,PartialEq,Eq,Hash)]
+pub enum TimeBase {
+    ///`/s`
+    #[default]
+    Second,
+    ///`/min`
+    Minute,
+    ///`/h`
+    Hour,
+}
+
+impl TimeBase {
+
+    ///Length of the span.
+    pub fn duration(self) -> Duration {
+        match self {
+            TimeBase::Second => Duration::from_secs(1),
+            TimeBase::Minute => Duration::from_secs(60),
+            TimeBase::Hour => Duration::from_secs(60 * 60),
+        }
+    }
+
+    ///Suffix written after the unit, such as `/s`.
+    pub fn symbol(self) -> &'static str {
+        match self {
+            TimeBase::Second => "/s",
+            TimeBase::Minute => "/min",
+            TimeBase::Hour => "/h",
+        }
+    }
+
+    ///Long name, such as `second`.
+    pub fn name(self) -> &'static str {
+        match self {
+            TimeBase::Second => "second",
+            TimeBase::Minute => "minute",
+            TimeBase::Hour => "hour",
+        }
+    }
+}
+
+///An amount transferred over a span of time, displayed per `TimeBase`.
+///
+///The amount and the time it took are kept as given; the amount per
+///time base is worked out when needed, discarding fractions of a byte
+///(or bit).
+#[derive(Clone,Copy)]
+pub struct Rate<U> {
+    amount: Size<U>,
+    elapsed: Duration,
+    base: TimeBase,
+}
+
+impl<U: Unit> Rate<U> {
+
+    ///`amount` transferred in `elapsed`, displayed per second. `None` if
+    ///`elapsed` is zero.
+    pub fn new(amount: Size<U>, elapsed: Duration) -> Option<Rate<U>> {
+        if elapsed.as_nanos() == 0 {
+            return None;
+        }
+        Some(Rate { amount, elapsed, base: TimeBase::Second })
+    }
+
+    ///A rate already known per second.
+    pub fn per_second(amount: Size<U>) -> Rate<U> {
+        Rate { amount, elapsed: TimeBase::Second.duration(), base: TimeBase::Second }
+    }
+
+    ///The same rate displayed over another time base.
+    pub fn per(mut self, base: TimeBase) -> Rate<U> {
+        self.base = base;
+        self
+    }
+
+    ///The time base the rate is displayed over.
+    pub fn base(&self) -> TimeBase {
+        self.base
+    }
+
+    ///Amount transferred per time base, saturating if it does not fit.
+    pub fn amount(&self) -> Size<U> {
+        let elapsed = self.elapsed.as_nanos();
+        let base = self.base.duration().as_nanos();
+        //split so the multiplication only overflows for absurd durations
+        let whole = (self.amount.bytes / elapsed).saturating_mul(base);
+        let part = (self.amount.bytes % elapsed).saturating_mul(base) / elapsed;
+        Size::from_parts(self.amount.negative, whole.saturating_add(part))
+    }
+
+    ///Builder for displaying the rate with non-default options. The
+    ///time base is written after the unit.
+    pub fn format(&self) -> Formatted<U> {
+        self.amount().format().with_per(self.base)
+    }
+}
+
+///Honors the same format string options as `Size`, e.g. `{:.1}` gives
+///`12.4MiB/s` and `{:#}` gives `12.4 mebibytes per second`.
+impl<U: Unit> fmt::Display for Rate<U> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.format(), f)
+    }
+}
+
+impl<U: Unit> fmt::Debug for Rate<U> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}{}", self.amount(), self.base.symbol())
+    }
+}
+
+///Parses the output of `Display`, such as `12.40MiB/s` or `3 GB/h`.
+///The time base may also be written `sec`, `minute` or `hour`, and
+///`ps` stands for per second as in `100Mbps` or `5 MBps`.
+impl<U: Unit> FromStr for Rate<U> {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Rate<U>, ParseError> {
+        let (amount, base) = match (s.rfind('/'), s.trim_end().strip_suffix("ps")) {
+            (Some(split), _) => (&s[..split], &s[split+1..]),
+            (None, Some(amount)) => (amount, "s"),
+            (None, None) => return Err(ParseError::UnknownUnit),
+        };
+        let base = match base.trim() {
+            "s" | "sec" | "second" => TimeBase::Second,
+            "min" | "minute" => TimeBase::Minute,
+            "h" | "hr" | "hour" => TimeBase::Hour,
+            _ => return Err(ParseError::UnknownUnit),
+        };
+        let amount: Size<U> = amount.parse()?;
+        Ok(Rate { amount, elapsed: base.duration(), base })
+    }
+}
+#[test]
+fn test_rate() {
+    let rate = Rate::new(IEC::new(62 << 20), Duration::from_secs(5)).unwrap();
+    assert_eq!(format!("{}", rate), "12.40MiB/s");
+    assert_eq!(format!("{:.1}", rate), "12.4MiB/s");
+    assert_eq!(format!("{}", rate.per(TimeBase::Minute)), "744.00MiB/min");
+    assert_eq!(format!("{}", rate.per(TimeBase::Hour)), "43.59GiB/h");
+    assert_eq!(format!("{:#.0}", Rate::per_second(IEC::new(1))), "1 byte per second");
+    assert_eq!(format!("{:>12}", rate), "  12.40MiB/s");
+    let rate = Rate::new(IEC::new(1500), Duration::from_millis(1500)).unwrap();
+    assert_eq!(format!("{}", rate.format().separator(Separator::Space).style(Style::Short)), "1000.00 B/s");
+    assert_eq!(rate.amount().unit(), IecUnit::B);
+    assert!(Rate::new(IEC::new(1), Duration::from_secs(0)).is_none());
+    let link: SIBits = IEC::new(125_000_000).convert();
+    assert_eq!(format!("{}", Rate::per_second(link)), "1.00Gbit/s");
+}
+#[test]
+fn test_parse_rate() {
+    let rate: Rate<IecUnit> = "12.40MiB/s".parse().unwrap();
+    assert_eq!(format!("{}", rate), "12.40MiB/s");
+    let rate: Rate<IecUnit> = "3 GiB / h".parse().unwrap();
+    assert_eq!(rate.base(), TimeBase::Hour);
+    assert_eq!(rate.amount(), IEC::new(3 << 30));
+    assert_eq!(format!("{}", rate.per(TimeBase::Minute)), "51.20MiB/min");
+    assert_eq!("5MiB/day".parse::<Rate<IecUnit>>().err(), Some(ParseError::UnknownUnit));
+    assert_eq!("5MiB".parse::<Rate<IecUnit>>().err(), Some(ParseError::UnknownUnit));
+    assert_eq!("x/s".parse::<Rate<IecUnit>>().err(), Some(ParseError::InvalidNumber));
+    let link: Rate<SiBitUnit> = "1 Gbps".parse().unwrap();
+    assert_eq!(format!("{}", link), "1.00Gbit/s");
+    let rate: Rate<SiUnit> = "100Mbps".parse().unwrap();
+    assert_eq!(format!("{}", rate), "12.50MB/s");
+    let rate: Rate<SiUnit> = "5 MBps".parse().unwrap();
+    assert_eq!(format!("{}", rate), "5.00MB/s");
+}
@@ -0,0 +1,172 @@
+//!Serde support, enabled with the `serde` feature.
+//!
+//!`Size` serializes as its byte count by default. The `bytes` and
+//!`human` modules pick an encoding explicitly for use with
+//!`#[serde(with = "humannums::serde::human")]`.
+//!
+//!In human readable formats such as JSON or TOML every form
+//!deserializes from either a number of bytes, such as `1610612736`, or
+//!a string, such as `"1.5GiB"`. Compact formats such as postcard carry
+//!no type information, so there each encoding reads back only itself.
+
+use core::fmt;
+use core::marker::PhantomData;
+
+use serde_crate::de::{self, Deserializer, Visitor};
+use serde_crate::ser::{self, Serializer};
+use serde_crate::{Deserialize, Serialize};
+
+use format::MAX_DIGITS;
+use {Size, Unit};
+#[cfg(test)]
+use {IecUnit, IECBits, IEC, SI};
+
+impl<U: Unit> Serialize for Size<U> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        bytes::serialize(self, serializer)
+    }
+}
+
+impl<'de, U: Unit> Deserialize<'de> for Size<U> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Size<U>, D::Error> {
+        bytes::deserialize(deserializer)
+    }
+}
+
+///Accepts byte counts and strings.
+struct SizeVisitor<U>(PhantomData<U>);
+
+impl<'de, U: Unit> Visitor<'de> for SizeVisitor<U> {
+    type Value = Size<U>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a number of bytes or a size such as \"1.5GiB\"")
+    }
+
+    fn visit_u64<E: de::Error>(self, x: u64) -> Result<Size<U>, E> {
+        Ok(Size::new(x))
+    }
+
+    fn visit_u128<E: de::Error>(self, x: u128) -> Result<Size<U>, E> {
+        Ok(Size::from_u128(x))
+    }
+
+    fn visit_i64<E: de::Error>(self, x: i64) -> Result<Size<U>, E> {
+        Ok(Size::new_signed(x))
+    }
+
+    fn visit_i128<E: de::Error>(self, x: i128) -> Result<Size<U>, E> {
+        Ok(Size::from_i128(x))
+    }
+
+    fn visit_str<E: de::Error>(self, s: &str) -> Result<Size<U>, E> {
+        s.parse().map_err(E::custom)
+    }
+}
+
+///Serialize as the exact byte count, e.g. `1610612736`.
+///
+///In human readable formats, counts that do not fit in 64 bits are
+///written as a string with the smallest unit of the family, such as
+///`"1180591620717411303424B"`, since JSON readers take large numbers
+///as floats. Compact formats get a 128 bit integer, which fails to
+///serialize only for magnitudes past `i128::MAX`.
+pub mod bytes {
+    use super::*;
+
+    pub fn serialize<U: Unit, S: Serializer>(size: &Size<U>, serializer: S) -> Result<S::Ok, S::Error> {
+        let (negative, bytes) = (size.negative, size.bytes);
+        if serializer.is_human_readable() {
+            match negative {
+                false if bytes <= u64::MAX as u128 => serializer.serialize_u64(bytes as u64),
+                true if bytes <= i64::MAX as u128 + 1 => serializer.serialize_i64((bytes as i64).wrapping_neg()),
+                _ => {
+                    let sign = if negative { "-" } else { "" };
+                    serializer.collect_str(&format_args!("{}{}{}", sign, bytes, U::ALL[0].symbol()))
+                }
+            }
+        } else if bytes <= i128::MAX as u128 {
+            let bytes = bytes as i128;
+            serializer.serialize_i128(if negative { -bytes } else { bytes })
+        } else if negative && bytes == i128::MAX as u128 + 1 {
+            serializer.serialize_i128(i128::MIN)
+        } else {
+            Err(ser::Error::custom("size is too large to serialize as an i128"))
+        }
+    }
+
+    ///Accepts a byte count or, in human readable formats, a string.
+    pub fn deserialize<'de, U: Unit, D: Deserializer<'de>>(deserializer: D) -> Result<Size<U>, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(SizeVisitor(PhantomData))
+        } else {
+            deserializer.deserialize_i128(SizeVisitor(PhantomData))
+        }
+    }
+}
+
+///Serialize as a string such as `"1.5GiB"`.
+///
+///Every digit needed is written, so `1535` bytes is `"1.4990234375KiB"`
+///and the value reads back exactly.
+pub mod human {
+    use super::*;
+
+    pub fn serialize<U: Unit, S: Serializer>(size: &Size<U>, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&size.format().precision(MAX_DIGITS).trim_zeros(true))
+    }
+
+    ///Accepts a string or, in human readable formats, a byte count.
+    pub fn deserialize<'de, U: Unit, D: Deserializer<'de>>(deserializer: D) -> Result<Size<U>, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(SizeVisitor(PhantomData))
+        } else {
+            deserializer.deserialize_str(SizeVisitor(PhantomData))
+        }
+    }
+}
+#[test]
+fn test_serde() {
+    let x = IEC::new(1_610_612_736);
+    assert_eq!(serde_json::to_string(&x).unwrap(), "1610612736");
+    assert_eq!(serde_json::to_string(&IEC::new_signed(-5)).unwrap(), "-5");
+    let mut text = std::vec::Vec::new();
+    human::serialize(&x, &mut serde_json::Serializer::new(&mut text)).unwrap();
+    assert_eq!(text, b"\"1.5GiB\"");
+    let mut text = std::vec::Vec::new();
+    human::serialize(&IEC::new(1535), &mut serde_json::Serializer::new(&mut text)).unwrap();
+    assert_eq!(text, b"\"1.4990234375KiB\"");
+    for json in &["1610612736", "\"1.5GiB\"", "\"1610612736\""] {
+        assert_eq!(serde_json::from_str::<IEC>(json).unwrap(), x);
+    }
+    assert_eq!(serde_json::from_str::<SI>("\"1.5 GB\"").unwrap(), SI::new(1_500_000_000));
+    assert_eq!(serde_json::from_str::<IEC>("-5").unwrap(), IEC::new_signed(-5));
+    assert!(serde_json::from_str::<IEC>("\"5 fortnights\"").is_err());
+    assert!(serde_json::from_str::<IEC>("1.5").is_err());
+    let exact = human::deserialize::<IecUnit, _>(&mut serde_json::Deserializer::from_str("\"1.4990234375KiB\"")).unwrap();
+    assert_eq!(exact, IEC::new(1535));
+}
+#[test]
+fn test_serde_wide() {
+    for &x in &[IEC::from_u128(1 << 70), IEC::from_i128(i128::MIN), IEC::from_u128(u128::MAX)] {
+        let json = serde_json::to_string(&x).unwrap();
+        assert_eq!(serde_json::from_str::<IEC>(&json).unwrap(), x);
+    }
+    assert_eq!(serde_json::to_string(&IEC::from_u128(1 << 70)).unwrap(), "\"1180591620717411303424B\"");
+    let bits = IECBits::from_u128(1 << 70);
+    assert_eq!(serde_json::from_str::<IECBits>(&serde_json::to_string(&bits).unwrap()).unwrap(), bits);
+}
+#[test]
+fn test_serde_compact() {
+    for &x in &[IEC::new(1536), IEC::new_signed(-5), IEC::from_u128(1 << 70), IEC::from_i128(i128::MIN)] {
+        let data = postcard::to_allocvec(&x).unwrap();
+        assert_eq!(postcard::from_bytes::<IEC>(&data).unwrap(), x);
+    }
+    assert!(postcard::to_allocvec(&IEC::from_u128(u128::MAX)).is_err());
+    use postcard::ser_flavors::{AllocVec, Flavor};
+    let mut output = postcard::Serializer { output: AllocVec::new() };
+    human::serialize(&IEC::new(1535), &mut output).unwrap();
+    let data = output.output.finalize().unwrap();
+    let x = human::deserialize::<IecUnit, _>(&mut postcard::Deserializer::from_bytes(&data));
+    assert_eq!(x.unwrap(), IEC::new(1535));
+}
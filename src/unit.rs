@@ -0,0 +1,292 @@
+//!Unit prefixes and their metadata.
+
+use core::fmt;
+
+pub(crate) const IEC_PREFIX: [u128; 11] = [
+    1,
+    1024,
+    1024*1024,
+    1024*1024*1024,
+    1024*1024*1024*1024,
+    1024*1024*1024*1024*1024,
+    1024*1024*1024*1024*1024*1024,
+    1024*1024*1024*1024*1024*1024*1024,
+    1024*1024*1024*1024*1024*1024*1024*1024,
+    1024*1024*1024*1024*1024*1024*1024*1024*1024,
+    1024*1024*1024*1024*1024*1024*1024*1024*1024*1024
+];
+
+pub(crate) const SI_PREFIX: [u128; 11] = [
+    1,
+    1000,
+    1000*1000,
+    1000*1000*1000,
+    1000*1000*1000*1000,
+    1000*1000*1000*1000*1000,
+    1000*1000*1000*1000*1000*1000,
+    1000*1000*1000*1000*1000*1000*1000,
+    1000*1000*1000*1000*1000*1000*1000*1000,
+    1000*1000*1000*1000*1000*1000*1000*1000*1000,
+    1000*1000*1000*1000*1000*1000*1000*1000*1000*1000
+];
+
+///Letters the prefixes past the first start with, shared by every
+///family: `K` for `KiB`, `KB` and `Kibit`, and so on.
+pub(crate) const PREFIX_LETTERS: [u8; 10] = *b"KMGTPEZYRQ";
+
+///
+///Find Position within prefix array
+///
+///Values below `1024` (including `0`) map to bytes, and everything
+///at or above `1024^10` maps to the largest prefix.
+///
+#[inline(always)]
+const fn iec_position(x: u128) -> usize {
+    let mut item = 10;
+    while item > 0 && x < IEC_PREFIX[item] {
+        item -= 1;
+    }
+    item
+}
+#[test]
+fn test_iec_position() {
+    assert_eq!(iec_position(0),0);
+    assert_eq!(iec_position(5),0);
+    assert_eq!(iec_position(1023),0);
+    assert_eq!(iec_position(1024),1);
+    assert_eq!(iec_position(5000),1);
+    assert_eq!(iec_position(1073741824),3);
+    assert_eq!(iec_position(IEC_PREFIX[6]),6);
+    assert_eq!(iec_position(u64::MAX as u128),6);
+    assert_eq!(iec_position(IEC_PREFIX[10]),10);
+    assert_eq!(iec_position(u128::MAX),10);
+}
+
+///
+///Find Position within SI prefix array
+///
+#[inline(always)]
+const fn si_position(x: u128) -> usize {
+    let mut item = 10;
+    while item > 0 && x < SI_PREFIX[item] {
+        item -= 1;
+    }
+    item
+}
+#[test]
+fn test_si_position() {
+    assert_eq!(si_position(0),0);
+    assert_eq!(si_position(999),0);
+    assert_eq!(si_position(1000),1);
+    assert_eq!(si_position(5000),1);
+    assert_eq!(si_position(1000000000),3);
+    assert_eq!(si_position(u64::MAX as u128),6);
+    assert_eq!(si_position(u128::MAX),10);
+}
+
+///
+///Exponent of the largest prefix of the family with `base` that does
+///not exceed `x`, which is also its index in `Unit::ALL`.
+///
+#[inline(always)]
+pub(crate) const fn position(base: u128, x: u128) -> usize {
+    if base == IEC_PREFIX[1] {
+        iec_position(x)
+    } else {
+        si_position(x)
+    }
+}
+
+///A family of prefixes that a `Size` can be expressed in.
+///
+///Implemented by `IecUnit` and `SiUnit` for bytes, and by `IecBitUnit`
+///and `SiBitUnit` for bits. They carry everything there is to know
+///about each prefix so callers can list or validate units without
+///hardcoding them.
+///
+///The trait is sealed: `Size` relies on every family having the same
+///eleven powers of 1024 or 1000, so it cannot be implemented outside
+///this crate.
+pub trait Unit: private::Sealed + Copy + PartialEq + fmt::Debug + 'static {
+    ///Name of the family, used by `Debug`.
+    const FAMILY: &'static str;
+
+    ///Base the prefixes are powers of, `1024` or `1000`.
+    const BASE: u128;
+
+    ///Bits in the quantity the family counts: `8` for bytes, `1` for bits.
+    const BITS: u128;
+
+    ///Every unit of the family, smallest first.
+    const ALL: &'static [Self];
+
+    ///Number of bytes (or bits) in one of this unit, `BASE` to the
+    ///`exponent()`.
+    fn multiplier(self) -> u128;
+
+    ///Power of `BASE` this unit stands for, e.g. `2` for `MiB`.
+    fn exponent(self) -> u32;
+
+    ///Symbol written after the value, such as `KiB`.
+    fn symbol(self) -> &'static str;
+
+    ///Singular long name, such as `kibibyte`.
+    fn name(self) -> &'static str;
+
+    ///The unit whose symbol is exactly `symbol`.
+    fn from_symbol(symbol: &str) -> Option<Self> {
+        Self::ALL.iter().cloned().find(|unit| unit.symbol() == symbol)
+    }
+}
+
+mod private {
+    ///Keeps `Unit` from being implemented outside the crate.
+    pub trait Sealed {}
+}
+
+macro_rules! unit_family {
+    ($(#[$meta: meta])* $name: ident, $family: expr, $bits: expr, $table: ident,
+     $($variant: ident => $symbol: expr, $long: expr;)*) => {
+        $(#[$meta])*
+        #[derive(Clone,Copy,Debug,PartialEq,Eq,PartialOrd,Ord,Hash)]
+        pub enum $name {
+            $($variant,)*
+        }
+
+        impl Unit for $name {
+            const FAMILY: &'static str = $family;
+            const BASE: u128 = $table[1];
+            const BITS: u128 = $bits;
+            const ALL: &'static [$name] = &[$($name::$variant,)*];
+
+            #[inline(always)]
+            fn multiplier(self) -> u128 {
+                $table[self as usize]
+            }
+
+            #[inline(always)]
+            fn exponent(self) -> u32 {
+                self as u32
+            }
+
+            #[inline(always)]
+            fn symbol(self) -> &'static str {
+                match self {
+                    $($name::$variant => $symbol,)*
+                }
+            }
+
+            #[inline(always)]
+            fn name(self) -> &'static str {
+                match self {
+                    $($name::$variant => $long,)*
+                }
+            }
+        }
+
+        impl private::Sealed for $name {}
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.pad(self.symbol())
+            }
+        }
+    };
+}
+
+unit_family!(
+    ///IEC/JEDEC binary prefixes.
+    ///
+    ///Please note: these are not SI prefixes. They are defined by powers of
+    ///1024 not 1000 like SI.
+    IecUnit, "IEC", 8, IEC_PREFIX,
+    B => "B", "byte";
+    KiB => "KiB", "kibibyte";
+    MiB => "MiB", "mebibyte";
+    GiB => "GiB", "gibibyte";
+    TiB => "TiB", "tebibyte";
+    PiB => "PiB", "pebibyte";
+    EiB => "EiB", "exbibyte";
+    ZiB => "ZiB", "zebibyte";
+    YiB => "YiB", "yobibyte";
+    RiB => "RiB", "robibyte";
+    QiB => "QiB", "quebibyte";
+);
+
+unit_family!(
+    ///SI decimal prefixes.
+    ///
+    ///These are defined by powers of 1000, which is how disk vendors
+    ///and network equipment generally report sizes.
+    SiUnit, "SI", 8, SI_PREFIX,
+    B => "B", "byte";
+    KB => "KB", "kilobyte";
+    MB => "MB", "megabyte";
+    GB => "GB", "gigabyte";
+    TB => "TB", "terabyte";
+    PB => "PB", "petabyte";
+    EB => "EB", "exabyte";
+    ZB => "ZB", "zettabyte";
+    YB => "YB", "yottabyte";
+    RB => "RB", "ronnabyte";
+    QB => "QB", "quettabyte";
+);
+
+unit_family!(
+    ///IEC binary prefixes applied to bits.
+    IecBitUnit, "IECBits", 1, IEC_PREFIX,
+    Bit => "bit", "bit";
+    Kibit => "Kibit", "kibibit";
+    Mibit => "Mibit", "mebibit";
+    Gibit => "Gibit", "gibibit";
+    Tibit => "Tibit", "tebibit";
+    Pibit => "Pibit", "pebibit";
+    Eibit => "Eibit", "exbibit";
+    Zibit => "Zibit", "zebibit";
+    Yibit => "Yibit", "yobibit";
+    Ribit => "Ribit", "robibit";
+    Qibit => "Qibit", "quebibit";
+);
+
+unit_family!(
+    ///SI decimal prefixes applied to bits, as used for link speeds.
+    SiBitUnit, "SIBits", 1, SI_PREFIX,
+    Bit => "bit", "bit";
+    Kbit => "kbit", "kilobit";
+    Mbit => "Mbit", "megabit";
+    Gbit => "Gbit", "gigabit";
+    Tbit => "Tbit", "terabit";
+    Pbit => "Pbit", "petabit";
+    Ebit => "Ebit", "exabit";
+    Zbit => "Zbit", "zettabit";
+    Ybit => "Ybit", "yottabit";
+    Rbit => "Rbit", "ronnabit";
+    Qbit => "Qbit", "quettabit";
+);
+
+#[test]
+fn test_unit_metadata() {
+    for units in &[IecUnit::ALL.len(), SiUnit::ALL.len()] {
+        assert_eq!(*units, 11);
+    }
+    for (index, unit) in IecUnit::ALL.iter().enumerate() {
+        assert_eq!(unit.exponent() as usize, index);
+        assert_eq!(unit.multiplier(), IecUnit::BASE.pow(unit.exponent()));
+        assert_eq!(IecUnit::from_symbol(unit.symbol()), Some(*unit));
+    }
+    for unit in SiUnit::ALL {
+        assert_eq!(unit.multiplier(), SiUnit::BASE.pow(unit.exponent()));
+        assert_eq!(SiUnit::from_symbol(unit.symbol()), Some(*unit));
+    }
+    assert_eq!(IecUnit::KiB.name(), "kibibyte");
+    assert_eq!(SiUnit::QB.name(), "quettabyte");
+    assert_eq!(format!("{}", IecUnit::MiB), "MiB");
+    assert_eq!(IecUnit::from_symbol("MB"), None);
+    assert_eq!(SiBitUnit::from_symbol("kbit"), Some(SiBitUnit::Kbit));
+    assert_eq!(IecBitUnit::Mibit.multiplier(), 1 << 20);
+    for (unit, letter) in IecUnit::ALL[1..].iter().zip(PREFIX_LETTERS.iter()) {
+        assert_eq!(unit.symbol().as_bytes()[0], *letter);
+    }
+    assert_eq!(IecUnit::BITS, 8);
+    assert_eq!(SiBitUnit::BITS, 1);
+}